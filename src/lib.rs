@@ -59,45 +59,805 @@
 //! | COM4 | wch.cn | USB-SERIAL CH340 | 1A86:7523 |
 //! +------+--------+------------------+-----------+
 //! ```
+#![allow(clippy::needless_return)]
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+mod usb_descriptors;
+#[cfg(feature = "usb-ids")]
+mod usb_ids;
+
+pub use usb_descriptors::{
+    DescriptorError, UsbClassDescriptor, UsbConfigDescriptor, UsbDescriptors,
+    UsbDeviceDescriptor, UsbEndpointDescriptor, UsbInterfaceAssociation, UsbInterfaceDescriptor,
+    UsbTransferType,
+};
 
 #[cfg(target_os = "windows")]
 mod win;
 
 #[cfg(target_os = "windows")]
-pub use win::get_serial_list;
+pub use win::{get_serial_list, try_get_serial_list};
 
 #[cfg(target_os = "linux")]
 mod linux;
 
 #[cfg(target_os = "linux")]
-pub use linux::get_serial_list;
+pub use linux::{
+    get_serial_list, get_serial_list_from, get_serial_list_with, list_tty_drivers,
+    list_tty_drivers_from, try_get_serial_list, try_get_serial_list_from, try_get_serial_list_with,
+    try_list_tty_drivers, try_list_tty_drivers_from, EnumerationOptions, EnumerationRoot,
+};
 
 #[cfg(target_os = "macos")]
 mod macos;
 
 #[cfg(target_os = "macos")]
-pub use macos::get_serial_list;
+pub use macos::{get_serial_list, try_get_serial_list};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 /// usb information of serial port
 pub struct UsbInfo {
     /// Vendor ID
     pub vid: String,
     /// Product ID
     pub pid: String,
+    /// serial number string of the usb device
+    pub serial_number: Option<String>,
+    /// device release number (bcdDevice), as hex string
+    pub bcd_device: Option<String>,
+    /// number of the usb interface which provides the serial port
+    pub interface_number: Option<u8>,
+    /// interface string of the usb interface, for example of a multi-port FT2232
+    pub interface: Option<String>,
+    /// linux only, bus, address and hub port of the usb device
+    pub location: Option<UsbLocation>,
+    /// linux only, speed, power and connection of the usb device
+    pub link: Option<UsbLinkInfo>,
+    /// linux only, descriptors of the usb device from the `descriptors` file in sysfs
+    pub descriptors: Option<UsbDescriptors>,
+    /// linux only, active alternate setting of the interface which provides the serial port,
+    /// with its endpoints
+    pub interface_descriptor: Option<UsbInterfaceDescriptor>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// linux only, `connect_type` of the hub port a usb device is attached to
+pub enum UsbConnectType {
+    /// the port is accessible to the user, for example an external connector
+    Hotplug,
+    /// the device is soldered to the board or connected internally
+    Hardwired,
+    NotUsed,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, usb link attributes of a usb device in sysfs
+pub struct UsbLinkInfo {
+    /// negotiated speed in Mbit/s, for example `480` or `1.5`
+    pub speed: Option<String>,
+    /// usb version of the device, for example `2.00`
+    pub version: Option<String>,
+    /// `bMaxPower` of the active configuration in mA
+    pub max_power: Option<u32>,
+    /// `bmAttributes` of the active configuration
+    pub attributes: Option<u8>,
+    /// the device can be unplugged, `None` if the hub does not report it
+    pub removable: Option<bool>,
+    /// connect type of the hub port, which distinguishes built-in devices
+    /// from user-plugged devices
+    pub connect_type: Option<UsbConnectType>,
+    /// the device is authorized to be used
+    pub authorized: Option<bool>,
+}
+
+impl UsbLinkInfo {
+    /// bit 6 of `bmAttributes`
+    pub fn self_powered(&self) -> Option<bool> {
+        return self.attributes.map(|attributes| attributes & 0x40 != 0);
+    }
+
+    /// bit 5 of `bmAttributes`
+    pub fn remote_wakeup(&self) -> Option<bool> {
+        return self.attributes.map(|attributes| attributes & 0x20 != 0);
+    }
+}
+
+#[cfg(feature = "usb-ids")]
+impl UsbInfo {
+    /// vendor name of `vid` in the compiled-in usb.ids table
+    pub fn vendor_name(&self) -> Option<&'static str> {
+        return usb_ids::vendor_name(&self.vid);
+    }
+
+    /// product name of `vid` and `pid` in the compiled-in usb.ids table
+    pub fn product_name(&self) -> Option<&'static str> {
+        return usb_ids::product_name(&self.vid, &self.pid);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// physical location of a usb serial port
+pub struct UsbLocation {
+    /// usb bus number
+    pub busnum: u16,
+    /// device address on the bus
+    pub devnum: u16,
+    /// bus and hub ports from the root hub, for example `1-2.3.1`
+    pub port_path: String,
+    /// configuration and interface of the serial port, for example `1.0`
+    pub interface: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, kernel driver bound to a device
+pub struct DriverInfo {
+    /// driver name, for example `ch341`
+    pub name: String,
+    /// kernel module of the driver, `None` if the driver is built into the kernel
+    pub module: Option<String>,
+    /// `MODULE_VERSION` of the module, which most in-tree modules do not set
+    pub version: Option<String>,
+    /// checksum of the module sources, which differs between in-tree and vendor modules
+    pub srcversion: Option<String>,
+    /// bus of the driver, for example `usb-serial`, `usb`, `platform` or `pci`
+    pub bus: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// kind of hardware behind a serial port
+pub enum PortKind {
+    /// the backing hardware was not identified
+    #[default]
+    Unknown,
+    /// usb serial adapter or usb cdc-acm device
+    Usb,
+    /// port of a pci or pcie serial card
+    Pci,
+    /// UART of the SoC or mainboard, described by device tree, ACPI or PNP
+    Builtin,
+    /// bluetooth rfcomm port
+    Bluetooth,
+    /// port without backing hardware, such as a pseudo terminal
+    Virtual,
+    /// serial function of a usb gadget, the device side of a usb connection
+    Gadget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// linux only, major and minor number of a character device
+pub struct DeviceNumber {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl fmt::Display for DeviceNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}:{}", self.major, self.minor);
+    }
+}
+
+#[derive(Debug, Default)]
 /// serial port informations
 pub struct SerialInfo {
-    /// serial port name
+    /// serial port name, on linux the device node if it was found,
+    /// otherwise the kernel name below `/dev`
     pub name: String,
+    /// linux only, device number from `/sys/class/tty/<name>/dev`
+    pub device_number: Option<DeviceNumber>,
+    /// linux only, character device node with a matching device number,
+    /// `None` if there is no such node, for example in a container without the device
+    pub device_node: Option<PathBuf>,
     /// vendor info
     pub vendor: Option<String>,
     /// product info
     pub product: Option<String>,
+    /// linux only, `vendor` is taken from the usb.ids table, not from the device
+    pub vendor_from_database: bool,
+    /// linux only, `product` is taken from the usb.ids table, not from the device
+    pub product_from_database: bool,
     /// linux only, driver name of current serial port
     pub driver: Option<String>,
+    /// linux only, driver bound to the device of the tty, such as `ftdi_sio` on `usb-serial`
+    pub driver_info: Option<DriverInfo>,
+    /// linux only, driver bound to the usb interface of a usb serial adapter,
+    /// if it is another device than the one of the tty
+    pub interface_driver: Option<DriverInfo>,
+    /// kind of hardware behind the port
+    pub kind: PortKind,
     /// usb serial port only, vid and pid provided
     pub usb_info: Option<UsbInfo>,
+    /// linux only, port of a pci or pcie serial card
+    pub pci_info: Option<PciInfo>,
+    /// linux only, UART of a built-in port
+    pub uart_info: Option<UartInfo>,
+    /// linux only, counters and modem lines of a `ttyS` port from `/proc/tty/driver/serial`,
+    /// which is only readable by root
+    pub uart_stats: Option<UartStats>,
+    /// linux only, CDC-ACM capabilities and interfaces of a `ttyACM` port
+    pub acm_info: Option<AcmInfo>,
+    /// linux only, device tree node of a built-in port
+    pub device_tree: Option<DeviceTreeInfo>,
+    /// linux only, RS-485 setup of a built-in port from device tree
+    pub rs485: Option<Rs485Config>,
+    /// linux only, bluetooth rfcomm port
+    pub bluetooth_info: Option<BluetoothInfo>,
+    /// linux only, usb gadget serial port
+    pub gadget_info: Option<GadgetInfo>,
+    /// linux only, the kernel uses the port as console
+    pub console: Option<ConsoleInfo>,
+    /// linux only, symlinks below `/dev` which point to this port,
+    /// such as `/dev/serial/by-id/...` or names created by udev rules,
+    /// and symlinks in [`EnumerationOptions::virtual_link_dirs`]
+    pub links: Vec<PathBuf>,
+    /// linux only, properties from the udev database,
+    /// `None` if udev is not available, for example in a container
+    pub udev: Option<UdevInfo>,
+    /// non-fatal errors met while probing this port
+    pub errors: Vec<EnumerationError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// pci information of serial port
+pub struct PciInfo {
+    /// Vendor ID
+    pub vendor: String,
+    /// Device ID
+    pub device: String,
+    /// Subsystem vendor ID, usually the card manufacturer
+    pub subsystem_vendor: Option<String>,
+    /// Subsystem device ID
+    pub subsystem_device: Option<String>,
+    /// class code, for example `070002` for a 16550 compatible serial controller
+    pub class: Option<String>,
+    /// pci address of the device, for example `0000:03:00.0`
+    pub slot: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, device tree node of a built-in port
+pub struct DeviceTreeInfo {
+    /// full path of the node, for example `/soc/serial@7e201000`
+    pub path: String,
+    /// `compatible` property, the most specific entry first
+    pub compatible: Vec<String>,
+    /// `status` property, for example `okay` or `disabled`
+    pub status: Option<String>,
+    /// `current-speed` property, the baud rate set up by firmware
+    pub current_speed: Option<u32>,
+    /// aliases of the node, for example `serial0`
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, bluetooth rfcomm information of serial port
+pub struct BluetoothInfo {
+    /// address of the remote device
+    pub address: Option<String>,
+    /// rfcomm channel of the remote device
+    pub channel: Option<u8>,
+    /// local adapter, for example `hci0`
+    pub adapter: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, usb gadget information of a `ttyGS` port from configfs
+pub struct GadgetInfo {
+    /// name of the gadget in `/sys/kernel/config/usb_gadget`,
+    /// `None` for gadgets of legacy modules such as `g_serial`
+    pub gadget: Option<String>,
+    /// function instance, for example `acm.usb0`
+    pub function: Option<String>,
+    /// usb device controller the gadget is bound to
+    pub udc: Option<String>,
+    /// the gadget is bound and the host has selected a configuration containing the function
+    pub configured: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, kernel console on a port
+pub struct ConsoleInfo {
+    /// the kernel prints messages to the port,
+    /// listed in `/proc/consoles` or `/sys/class/tty/console/active`
+    pub active: bool,
+    /// the port is the preferred console behind `/dev/console`
+    pub preferred: bool,
+    /// options of `console=` on the kernel command line, such as `115200n8`
+    pub options: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, RS-485 properties of a device tree node,
+/// which has at least one `rs485-*` or `linux,rs485-*` property
+pub struct Rs485Config {
+    /// `linux,rs485-enabled-at-boot-time`, the driver enables RS-485 mode on probe
+    pub enabled_at_boot_time: bool,
+    /// first cell of `rs485-rts-delay`, delay before send in milliseconds
+    pub rts_delay_before_send: Option<u32>,
+    /// second cell of `rs485-rts-delay`, delay after send in milliseconds
+    pub rts_delay_after_send: Option<u32>,
+    /// `rs485-rts-active-low`, RTS is low while sending
+    pub rts_active_low: bool,
+    /// `rs485-rx-during-tx`, the receiver stays enabled while sending
+    pub rx_during_tx: bool,
+    /// `rts-gpios`, RTS is driven by a gpio instead of the UART,
+    /// only reported along with `rs485-*` properties
+    pub rts_gpios: bool,
+    /// `rs485-term-gpios`, bus termination is switched by a gpio
+    pub term_gpios: bool,
+}
+
+impl Rs485Config {
+    /// the node has RS-485 properties, but RS-485 mode is not enabled at boot time,
+    /// so the port stays in RS-232 mode until it is set up by `TIOCSRS485`
+    pub fn is_half_configured(&self) -> bool {
+        return !self.enabled_at_boot_time;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, CDC-ACM function of a usb device
+pub struct AcmInfo {
+    /// `bmCapabilities` of the ACM functional descriptor, as used by the cdc_acm driver
+    pub capabilities: u8,
+    /// communication interface, which provides the tty
+    pub control_interface: Option<u8>,
+    /// data interface with the bulk endpoints
+    pub data_interface: Option<u8>,
+    /// string index of the release date of the country codes
+    pub country_code_release_index: Option<u8>,
+    /// ISO 3166 country codes of the country selection functional descriptor
+    pub country_codes: Vec<u16>,
+}
+
+impl AcmInfo {
+    /// Set_Comm_Feature, Clear_Comm_Feature and Get_Comm_Feature requests
+    pub fn supports_comm_features(&self) -> bool {
+        return self.capabilities & 0x01 != 0;
+    }
+
+    /// Set_Line_Coding, Set_Control_Line_State, Get_Line_Coding and Serial_State,
+    /// without them baud rate and framing can not be changed
+    pub fn supports_line_coding(&self) -> bool {
+        return self.capabilities & 0x02 != 0;
+    }
+
+    /// Send_Break request
+    pub fn supports_break(&self) -> bool {
+        return self.capabilities & 0x04 != 0;
+    }
+
+    /// Network_Connection notification
+    pub fn supports_network_connection(&self) -> bool {
+        return self.capabilities & 0x08 != 0;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, status of a port in `/proc/tty/driver/serial`
+pub struct UartStats {
+    /// UART type name, for example `16550A`
+    pub uart: String,
+    /// transmitted characters
+    pub tx: u64,
+    /// received characters
+    pub rx: u64,
+    /// framing errors
+    pub frame_errors: u64,
+    /// parity errors
+    pub parity_errors: u64,
+    /// received breaks
+    pub breaks: u64,
+    /// overruns of the UART receive FIFO
+    pub overruns: u64,
+    /// overruns of the tty receive buffer
+    pub buffer_overruns: u64,
+    pub rts: bool,
+    pub cts: bool,
+    pub dtr: bool,
+    pub dsr: bool,
+    /// carrier detect
+    pub cd: bool,
+    /// ring indicator
+    pub ri: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, UART hardware of a built-in port, from the tty in sysfs
+pub struct UartInfo {
+    /// UART type of serial core, see [`UartInfo::type_name`]
+    pub uart_type: u32,
+    /// io port base address
+    pub port: Option<u64>,
+    /// memory mapped io base address
+    pub iomem_base: Option<u64>,
+    /// interrupt line
+    pub irq: Option<u32>,
+    /// input clock of the UART in Hz
+    pub uartclk: Option<u64>,
+    /// size of the transmit fifo
+    pub xmit_fifo_size: Option<u32>,
+    /// register access method, such as `0` for io port and `2` for memory mapped io
+    pub io_type: Option<u32>,
+    /// close delay in centiseconds
+    pub close_delay: Option<u32>,
+    /// `UPF_*` flags of the port
+    pub flags: Option<u64>,
+    /// name of the device which provides the UART, for example `ff000000.serial`
+    pub platform_device: Option<String>,
+}
+
+impl UartInfo {
+    /// name of the UART type, for example `16550A`, or the `PORT_*` name without
+    /// prefix for UARTs of SoCs, such as `IMX` or `XUARTPS`
+    pub fn type_name(&self) -> Option<&'static str> {
+        // `PORT_*` ids of include/uapi/linux/serial_core.h
+        let name = match self.uart_type {
+            0 => "unknown",
+            1 => "8250",
+            2 => "16450",
+            3 => "16550",
+            4 => "16550A",
+            5 => "Cirrus",
+            6 => "16650",
+            7 => "16650V2",
+            8 => "16750",
+            9 => "Startech",
+            10 => "16C950",
+            11 => "16654",
+            12 => "16850",
+            13 => "RSA",
+            14 => "NS16550A",
+            15 => "XScale",
+            16 => "RM9000",
+            17 => "OCTEON",
+            18 => "AR7",
+            19 => "U6_16550A",
+            20 => "Tegra",
+            21 => "XR17D15X",
+            22 => "LPC3220",
+            23 => "8250_CIR",
+            24 => "XR17V35X",
+            25 => "TruManage",
+            26 => "Altera 16550 FIFO32",
+            27 => "Altera 16550 FIFO64",
+            28 => "Altera 16550 FIFO128",
+            29 => "RT2880",
+            30 => "16550A_FSL64",
+            31 => "PXA",
+            32 => "AMBA",
+            33 => "CLPS711X",
+            34 => "SA1100",
+            35 => "UART00",
+            36 => "OWL",
+            37 => "21285",
+            38 => "SUNZILOG",
+            39 => "SUNSAB",
+            40 => "NPCM",
+            41 => "TEGRA_TCU",
+            42 => "ASPEED_VUART",
+            44 => "PCH_8LINE",
+            45 => "PCH_2LINE",
+            46 => "DZ",
+            47 => "ZS",
+            48 => "MUX",
+            49 => "ATMEL",
+            50 => "MAC_ZILOG",
+            51 => "PMAC_ZILOG",
+            52 => "SCI",
+            53 => "SCIF",
+            54 => "IRDA",
+            55 => "S3C2410",
+            56 => "IP22ZILOG",
+            58 => "CPM",
+            59 => "MPC52xx",
+            60 => "ICOM",
+            61 => "S3C2440",
+            62 => "IMX",
+            64 => "TXX9",
+            65 => "VR41XX_SIU",
+            66 => "VR41XX_DSIU",
+            67 => "S3C2400",
+            68 => "M32R_SIO",
+            69 => "JSM",
+            72 => "SUNHV",
+            74 => "UARTLITE",
+            76 => "BCM7271",
+            77 => "SB1250_DUART",
+            78 => "MCF",
+            82 => "SC26XX",
+            83 => "SCIFA",
+            84 => "S3C6400",
+            86 => "MAX3100",
+            87 => "TIMBUART",
+            88 => "MSM",
+            89 => "BCM63XX",
+            90 => "APBUART",
+            91 => "ALTERA_JTAGUART",
+            92 => "ALTERA_UART",
+            93 => "SCIFB",
+            94 => "MAX310X",
+            95 => "DA830",
+            96 => "OMAP",
+            97 => "VT8500",
+            98 => "XUARTPS",
+            99 => "AR933X",
+            101 => "ARC",
+            102 => "RP2",
+            103 => "LPUART",
+            104 => "HSCIF",
+            105 => "ASC",
+            107 => "MEN_Z135",
+            108 => "SC16IS7XX",
+            109 => "MESON",
+            110 => "DIGICOLOR",
+            111 => "SPRD",
+            113 => "STM32",
+            114 => "MVEBU",
+            115 => "PIC32",
+            116 => "MPS2UART",
+            117 => "MTK_BTIF",
+            118 => "RDA",
+            119 => "MLB_USIO",
+            120 => "SIFIVE_V0",
+            121 => "SUNIX",
+            122 => "LINFLEXUART",
+            123 => "SUNPLUS",
+            _ => return None,
+        };
+        return Some(name);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, device record of the udev database in `/run/udev/data`
+pub struct UdevInfo {
+    /// device properties, such as `ID_VENDOR_FROM_DATABASE` or `ID_PATH`
+    pub properties: BTreeMap<String, String>,
+    /// tags of the device, such as `systemd` or `uaccess`
+    pub tags: Vec<String>,
+    /// symlinks created by udev rules, relative to `/dev`
+    pub links: Vec<String>,
+    /// `USEC_INITIALIZED`, monotonic time in microseconds when udev initialized the device
+    pub initialized_usec: Option<u64>,
+}
+
+impl UdevInfo {
+    /// value of a device property
+    pub fn property(&self, key: &str) -> Option<&str> {
+        return self.properties.get(key).map(String::as_str);
+    }
+
+    /// vendor name from the hardware database, `ID_VENDOR_FROM_DATABASE`
+    pub fn vendor_from_database(&self) -> Option<&str> {
+        return self.property("ID_VENDOR_FROM_DATABASE");
+    }
+
+    /// model name from the hardware database, `ID_MODEL_FROM_DATABASE`
+    pub fn model_from_database(&self) -> Option<&str> {
+        return self.property("ID_MODEL_FROM_DATABASE");
+    }
+
+    /// decoded `ID_VENDOR_ENC`, the vendor string of the device
+    pub fn vendor(&self) -> Option<String> {
+        return self.property("ID_VENDOR_ENC").map(decode_udev_string);
+    }
+
+    /// decoded `ID_MODEL_ENC`, the model string of the device
+    pub fn model(&self) -> Option<String> {
+        return self.property("ID_MODEL_ENC").map(decode_udev_string);
+    }
+
+    /// `ID_SERIAL`, vendor, model and serial number joined by udev
+    pub fn serial(&self) -> Option<&str> {
+        return self.property("ID_SERIAL");
+    }
+
+    /// `ID_PATH`, the persistent path of the device
+    pub fn path(&self) -> Option<&str> {
+        return self.property("ID_PATH");
+    }
+
+    /// `ID_USB_INTERFACE_NUM`, usb interface number as hex string
+    pub fn usb_interface_num(&self) -> Option<&str> {
+        return self.property("ID_USB_INTERFACE_NUM");
+    }
+
+    /// `ID_SEAT`, the seat the device is assigned to
+    pub fn seat(&self) -> Option<&str> {
+        return self.property("ID_SEAT");
+    }
+
+    /// `ID_MM_*` properties, hints for ModemManager such as `ID_MM_DEVICE_IGNORE`
+    pub fn modem_manager_properties(&self) -> impl Iterator<Item = (&str, &str)> {
+        return self
+            .properties
+            .iter()
+            .filter(|(key, _)| key.starts_with("ID_MM_"))
+            .map(|(key, value)| (key.as_str(), value.as_str()));
+    }
+}
+
+/// decode `\xNN` escapes of udev encoded properties such as `ID_MODEL_ENC`
+fn decode_udev_string(encoded: &str) -> String {
+    let raw = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'\\' && raw.get(i + 1) == Some(&b'x') && i + 4 <= raw.len() {
+            let hex = std::str::from_utf8(&raw[i + 2..i + 4]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        decoded.push(raw[i]);
+        i += 1;
+    }
+    return String::from_utf8_lossy(&decoded).into_owned();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, reason why the current process can not open a port for read and write
+pub enum AccessProblem {
+    /// the device node does not exist
+    NodeMissing,
+    /// the group of the node, such as `dialout` or `uucp`, grants access,
+    /// but the user is not a member of it
+    NotInGroup(String),
+    /// the user is a member of the group of the node in `/etc/group`,
+    /// but the process does not have the group yet, a new login is required
+    GroupNotActive(String),
+    /// the node has an access control list without an entry for the user,
+    /// for example because the user is not logged in at the local seat
+    AclMissing,
+    /// neither owner, group nor others are granted read and write access by the mode
+    ModeDenied,
+    /// the node is not accessible for another reason, such as a read-only file system
+    Other(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, ownership and permissions of a device node, see [`SerialInfo::access`]
+pub struct PortAccess {
+    /// the checked device node
+    pub path: PathBuf,
+    /// user id of the owner, `None` if the node is missing
+    pub uid: Option<u32>,
+    /// user name of the owner from `/etc/passwd`
+    pub owner: Option<String>,
+    /// group id of the node
+    pub gid: Option<u32>,
+    /// group name of the node from `/etc/group`
+    pub group: Option<String>,
+    /// permission bits of the node, for example `0o660`
+    pub mode: Option<u32>,
+    /// the current process can open the node for read and write
+    pub can_open: bool,
+    /// why the node can not be opened, empty if `can_open` is true
+    pub problems: Vec<AccessProblem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, process which has a port open
+pub struct PortHolder {
+    pub pid: u32,
+    /// command name from `/proc/<pid>/comm`
+    pub command: Option<String>,
+    /// real user id of the process
+    pub uid: Option<u32>,
+    /// user name of the process from `/etc/passwd`
+    pub user: Option<String>,
+    /// the process is a service known to grab serial ports,
+    /// such as ModemManager, brltty, gpsd, a getty or pppd
+    pub known_grabber: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, UUCP lock file of a port, such as `/var/lock/LCK..ttyUSB0`
+pub struct PortLock {
+    pub path: PathBuf,
+    /// pid written to the lock file, `None` if the content is malformed
+    pub pid: Option<u32>,
+    /// the locking process is still running, otherwise the lock is stale
+    pub alive: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// linux only, processes and lock files which keep a port busy, see [`SerialInfo::usage`]
+pub struct PortUsage {
+    /// processes with an open file descriptor of the port
+    pub holders: Vec<PortHolder>,
+    /// lock files of the port in the lock directories of [`EnumerationRoot`]
+    pub locks: Vec<PortLock>,
+    /// the file descriptors of some processes could not be read,
+    /// usually processes of other users if not running as root
+    pub incomplete: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, type column of `/proc/tty/drivers`
+pub enum TtyDriverKind {
+    /// `/dev/tty`, `/dev/console`, `/dev/ptmx` and the virtual console master
+    System,
+    /// virtual consoles `/dev/tty1` and following
+    Console,
+    /// pseudo terminal masters and slaves
+    Pty,
+    Serial,
+    /// type unknown to this crate
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// linux only, tty driver registered in the kernel, a line of `/proc/tty/drivers`
+pub struct TtyDriver {
+    /// driver name, for example `serial` or `usbserial`
+    pub name: String,
+    /// device node or prefix of device nodes below `/dev`, for example `ttyS`
+    pub prefix: String,
+    pub major: u32,
+    /// first and last minor number
+    pub minors: RangeInclusive<u32>,
+    pub kind: TtyDriverKind,
+    /// subtype after the colon, for example `slave` of `pty:slave`
+    pub subtype: Option<String>,
+}
+
+#[derive(Debug)]
+/// error of serial port enumeration
+pub enum EnumerationError {
+    /// sysfs, or the tty class directory in it, does not exist
+    SysfsMissing(PathBuf),
+    /// permission denied while reading the path
+    PermissionDenied(PathBuf),
+    /// line of the tty driver table which can not be parsed
+    MalformedDriverTable(String),
+    /// attribute file which is not valid UTF-8
+    NonUtf8Attribute(PathBuf),
+    /// binary usb descriptors which can not be parsed
+    MalformedDescriptors(PathBuf, DescriptorError),
+    /// other io error while reading the path
+    Io(PathBuf, std::io::Error),
+    /// error reported by the platform API
+    Platform(String),
+}
+
+impl fmt::Display for EnumerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnumerationError::SysfsMissing(path) => {
+                write!(f, "sysfs not found at {}", path.display())
+            }
+            EnumerationError::PermissionDenied(path) => {
+                write!(f, "permission denied: {}", path.display())
+            }
+            EnumerationError::MalformedDriverTable(line) => {
+                write!(f, "malformed tty driver table line: {:?}", line)
+            }
+            EnumerationError::NonUtf8Attribute(path) => {
+                write!(f, "attribute is not valid UTF-8: {}", path.display())
+            }
+            EnumerationError::MalformedDescriptors(path, e) => {
+                write!(f, "{}: {}", path.display(), e)
+            }
+            EnumerationError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            EnumerationError::Platform(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EnumerationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnumerationError::MalformedDescriptors(_, e) => Some(e),
+            EnumerationError::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
 }
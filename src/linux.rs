@@ -1,34 +1,155 @@
-use crate::{SerialInfo, UsbInfo};
+use crate::{
+    AccessProblem, AcmInfo, BluetoothInfo, ConsoleInfo, DeviceNumber, DeviceTreeInfo, DriverInfo,
+    EnumerationError, GadgetInfo, PciInfo, PortAccess, PortHolder, PortKind, PortLock, PortUsage,
+    Rs485Config, SerialInfo, TtyDriver, TtyDriverKind, UartInfo, UdevInfo, UsbConnectType,
+    UsbDescriptors, UsbInfo, UsbInterfaceDescriptor, UsbLinkInfo, UsbLocation,
+};
 use std::collections::HashMap;
+use std::convert::TryInto;
+use std::ffi::CString;
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::{Path, PathBuf};
 use std::vec::Vec;
 
+/// root directories used to enumerate serial ports on linux
+///
+/// The default points to the live system. Use [`EnumerationRoot::with_prefix`] to
+/// enumerate a chroot, a mounted rootfs of a target board or a captured fixture tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumerationRoot {
+    /// mount point of sysfs, `/sys` by default
+    pub sysfs: PathBuf,
+    /// mount point of procfs, `/proc` by default
+    pub procfs: PathBuf,
+    /// directory of device nodes, `/dev` by default
+    pub devfs: PathBuf,
+    /// runtime state directory holding the udev database, `/run` by default
+    pub run: PathBuf,
+    /// directory of the `passwd` and `group` databases, `/etc` by default
+    pub etc: PathBuf,
+    /// directories searched for UUCP lock files such as `LCK..ttyUSB0`, by default
+    /// `/run/lock`, `/var/lock`, `/var/spool/uucp` and `/var/spool/lock`
+    pub lock_dirs: Vec<PathBuf>,
+}
+
+/// directories of UUCP lock files, relative to the root directory
+const LOCK_DIRS: [&str; 4] = ["run/lock", "var/lock", "var/spool/uucp", "var/spool/lock"];
+
+impl Default for EnumerationRoot {
+    fn default() -> Self {
+        return EnumerationRoot {
+            sysfs: PathBuf::from("/sys"),
+            procfs: PathBuf::from("/proc"),
+            devfs: PathBuf::from("/dev"),
+            run: PathBuf::from("/run"),
+            etc: PathBuf::from("/etc"),
+            lock_dirs: LOCK_DIRS
+                .iter()
+                .map(|dir| Path::new("/").join(dir))
+                .collect(),
+        };
+    }
+}
+
+impl EnumerationRoot {
+    /// use `sys`, `proc`, `dev`, `run`, `etc` and lock directories under `prefix`
+    pub fn with_prefix<P: AsRef<Path>>(prefix: P) -> Self {
+        let prefix = prefix.as_ref();
+        return EnumerationRoot {
+            sysfs: prefix.join("sys"),
+            procfs: prefix.join("proc"),
+            devfs: prefix.join("dev"),
+            run: prefix.join("run"),
+            etc: prefix.join("etc"),
+            lock_dirs: LOCK_DIRS.iter().map(|dir| prefix.join(dir)).collect(),
+        };
+    }
+}
+
+/// options of serial port enumeration on linux
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnumerationOptions {
+    /// root directories to enumerate
+    pub root: EnumerationRoot,
+    /// also report legacy ports whose UART was not detected, such as the
+    /// `ttyS1` to `ttyS31` which `serial8250` registers on most x86 machines
+    pub include_phantom_ports: bool,
+    /// also report ports without backing hardware: pseudo terminals of devpts,
+    /// tty0tty and ttyV null-modem pairs and `ttyprintk`
+    pub include_virtual: bool,
+    /// directories searched for symlinks to pseudo terminals, such as links created by
+    /// `socat PTY,link=/tmp/ttyV0`, found links are reported in [`SerialInfo::links`]
+    pub virtual_link_dirs: Vec<PathBuf>,
+}
+
 mod tty_drivers_parser {
+    use nom::branch::alt;
     use nom::bytes::complete::{tag, take_till1};
-    use nom::character::complete::space1;
-    use nom::sequence::{preceded, separated_pair};
+    use nom::character::complete::{space0, space1, u32 as number_parser};
+    use nom::combinator::{all_consuming, map, opt};
+    use nom::sequence::{preceded, separated_pair, terminated, tuple};
     use nom::IResult;
 
-    fn class_prefix_parser(s: &str) -> IResult<&str, &str> {
+    use crate::{EnumerationError, TtyDriver, TtyDriverKind};
+
+    fn field_parser(s: &str) -> IResult<&str, &str> {
         return take_till1(|c: char| c.is_ascii_whitespace())(s);
     }
 
     fn prefix_parser(s: &str) -> IResult<&str, &str> {
-        return preceded(tag("/dev/"), class_prefix_parser)(s);
+        return preceded(tag("/dev/"), field_parser)(s);
     }
 
-    fn drivers_line_parser(s: &str) -> IResult<&str, (&str, &str)> {
-        return separated_pair(class_prefix_parser, space1, prefix_parser)(s);
+    fn minors_parser(s: &str) -> IResult<&str, (u32, u32)> {
+        return alt((
+            separated_pair(number_parser, tag("-"), number_parser),
+            map(number_parser, |minor| (minor, minor)),
+        ))(s);
     }
 
-    /// parse line of /proc/tty/drivers
-    pub fn parse_line(line: &str) -> Option<(String, String)> {
-        if line.ends_with("serial") {
-            let (_, (class, prefix)) = drivers_line_parser(line).expect(line);
-            return Some((class.into(), prefix.into()));
-        }
-        return None;
+    fn kind_parser(s: &str) -> IResult<&str, (&str, Option<&str>)> {
+        return tuple((
+            take_till1(|c: char| c == ':' || c.is_ascii_whitespace()),
+            opt(preceded(tag(":"), field_parser)),
+        ))(s);
+    }
+
+    fn drivers_line_parser(s: &str) -> IResult<&str, TtyDriver> {
+        let (s, (name, prefix, major, (first, last), (kind, subtype))) = all_consuming(tuple((
+            field_parser,
+            preceded(space1, prefix_parser),
+            preceded(space1, number_parser),
+            preceded(space1, minors_parser),
+            preceded(space1, terminated(kind_parser, space0)),
+        )))(s)?;
+        let kind = match kind {
+            "system" => TtyDriverKind::System,
+            "console" => TtyDriverKind::Console,
+            "pty" => TtyDriverKind::Pty,
+            "serial" => TtyDriverKind::Serial,
+            other => TtyDriverKind::Other(other.into()),
+        };
+        let driver = TtyDriver {
+            name: name.into(),
+            prefix: prefix.into(),
+            major,
+            minors: first..=last,
+            kind,
+            subtype: subtype.map(String::from),
+        };
+        return Ok((s, driver));
+    }
+
+    /// parse line of /proc/tty/drivers, for example
+    /// `serial               /dev/ttyS       4 64-111 serial`
+    pub fn parse_line(line: &str) -> Result<TtyDriver, EnumerationError> {
+        return match drivers_line_parser(line) {
+            Ok((_, driver)) => Ok(driver),
+            Err(_) => Err(EnumerationError::MalformedDriverTable(line.into())),
+        };
     }
 
     #[cfg(test)]
@@ -38,28 +159,275 @@ mod tty_drivers_parser {
         fn test_drivers_line_parse() {
             let result =
                 parse_line("serial               /dev/ttyS       4 64-111 serial").unwrap();
-            assert_eq!(result, ("serial".into(), "ttyS".into()));
+            assert_eq!(
+                result,
+                TtyDriver {
+                    name: "serial".into(),
+                    prefix: "ttyS".into(),
+                    major: 4,
+                    minors: 64..=111,
+                    kind: TtyDriverKind::Serial,
+                    subtype: None,
+                }
+            );
+            let result =
+                parse_line("/dev/tty             /dev/tty        5       0 system:/dev/tty")
+                    .unwrap();
+            assert_eq!(result.name, "/dev/tty");
+            assert_eq!(result.minors, 0..=0);
+            assert_eq!(result.kind, TtyDriverKind::System);
+            assert_eq!(result.subtype.as_deref(), Some("/dev/tty"));
+            let result =
+                parse_line("pty_slave            /dev/pts      136 0-1048575 pty:slave").unwrap();
+            assert_eq!(result.kind, TtyDriverKind::Pty);
+            assert_eq!(result.subtype.as_deref(), Some("slave"));
+        }
+
+        #[test]
+        fn test_drivers_line_malformed() {
+            assert!(parse_line("/dev/ttyS serial").is_err());
+            assert!(parse_line("serial /dev/ttyS 4 64- serial").is_err());
         }
     }
 }
 
-fn get_serial_prefix() -> HashMap<String, String> {
-    let mut serial_prefix = HashMap::new();
-    const TTY_DRIVERS: &str = "/proc/tty/drivers";
-
-    match fs::read(TTY_DRIVERS) {
-        Ok(result) => {
-            let tty_drivers_info = String::from_utf8(result).unwrap();
-            for line in tty_drivers_info.lines() {
-                match tty_drivers_parser::parse_line(line) {
-                    Some((class, prefix)) => {
-                        serial_prefix.insert(prefix, class);
+mod udev_db_parser {
+    use crate::UdevInfo;
+
+    /// parse a device record of /run/udev/data
+    pub fn parse(text: &str) -> UdevInfo {
+        let mut udev_info = UdevInfo::default();
+        for line in text.lines() {
+            match line.split_once(':') {
+                Some(("E", property)) => {
+                    if let Some((key, value)) = property.split_once('=') {
+                        udev_info.properties.insert(key.into(), value.into());
                     }
-                    None => {}
                 }
+                Some(("G", tag)) => udev_info.tags.push(tag.into()),
+                Some(("S", link)) => udev_info.links.push(link.into()),
+                Some(("I", usec)) => udev_info.initialized_usec = usec.parse().ok(),
+                _ => {}
             }
         }
-        Err(_e) => {
+        return udev_info;
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_parse() {
+            let udev_info = parse(
+                "S:serial/by-id/usb-1a86_USB2.0-Serial-if00-port0\n\
+                 I:5170419088\n\
+                 E:ID_VENDOR_FROM_DATABASE=QinHeng Electronics\n\
+                 E:ID_MODEL_ENC=USB2.0\\x20Serial\n\
+                 E:ID_MM_CANDIDATE=1\n\
+                 G:systemd\n\
+                 Q:systemd\n\
+                 V:1\n",
+            );
+            assert_eq!(
+                udev_info.vendor_from_database(),
+                Some("QinHeng Electronics")
+            );
+            assert_eq!(udev_info.model().as_deref(), Some("USB2.0 Serial"));
+            assert_eq!(udev_info.initialized_usec, Some(5170419088));
+            assert_eq!(udev_info.tags, ["systemd"]);
+            assert_eq!(
+                udev_info.links,
+                ["serial/by-id/usb-1a86_USB2.0-Serial-if00-port0"]
+            );
+            assert_eq!(
+                udev_info.modem_manager_properties().collect::<Vec<_>>(),
+                [("ID_MM_CANDIDATE", "1")]
+            );
+        }
+    }
+}
+
+mod consoles_parser {
+    use nom::bytes::complete::{tag, take_till, take_till1};
+    use nom::character::complete::space1;
+    use nom::sequence::{delimited, preceded, tuple};
+    use nom::IResult;
+
+    fn consoles_line_parser(s: &str) -> IResult<&str, (&str, &str)> {
+        let (s, (name, _, flags)) = tuple((
+            take_till1(|c: char| c.is_ascii_whitespace()),
+            preceded(space1, take_till1(|c: char| c.is_ascii_whitespace())),
+            preceded(
+                space1,
+                delimited(tag("("), take_till(|c: char| c == ')'), tag(")")),
+            ),
+        ))(s)?;
+        return Ok((s, (name, flags)));
+    }
+
+    /// parse line of /proc/consoles, for example
+    /// `ttyS0                -W- (EC p a)    4:64`
+    ///
+    /// returns tty name and console flags
+    pub fn parse_line(line: &str) -> Option<(String, String)> {
+        let (_, (name, flags)) = consoles_line_parser(line).ok()?;
+        return Some((name.into(), flags.into()));
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        #[test]
+        fn test_consoles_line_parse() {
+            assert_eq!(
+                parse_line("ttyS0                -W- (EC p a)    4:64"),
+                Some(("ttyS0".into(), "EC p a".into()))
+            );
+            assert_eq!(
+                parse_line("tty0                 -WU (E  p  )    4:1"),
+                Some(("tty0".into(), "E  p  ".into()))
+            );
+            assert_eq!(parse_line(""), None);
+        }
+    }
+}
+
+mod serial_driver_parser {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{space0, u32 as line_parser};
+    use nom::sequence::terminated;
+    use nom::IResult;
+
+    use crate::UartStats;
+
+    fn index_parser(s: &str) -> IResult<&str, u32> {
+        return terminated(line_parser, terminated(tag(":"), space0))(s);
+    }
+
+    /// parse line of /proc/tty/driver/serial, for example
+    /// `0: uart:16550A port:000003F8 irq:4 tx:123 rx:456 fe:1 brk:0 RTS|DTR`
+    ///
+    /// returns line number and status, `None` for lines without counters,
+    /// such as the header or ports without UART
+    pub fn parse_line(line: &str) -> Option<(u32, UartStats)> {
+        let (s, index) = index_parser(line).ok()?;
+        let mut stats = UartStats::default();
+        let mut has_counters = false;
+        for token in s.split_whitespace() {
+            match token.split_once(':') {
+                Some((key, value)) => {
+                    let counter = match key {
+                        "uart" => {
+                            stats.uart = value.into();
+                            continue;
+                        }
+                        "tx" => &mut stats.tx,
+                        "rx" => &mut stats.rx,
+                        "fe" => &mut stats.frame_errors,
+                        "pe" => &mut stats.parity_errors,
+                        "brk" => &mut stats.breaks,
+                        "oe" => &mut stats.overruns,
+                        "bo" => &mut stats.buffer_overruns,
+                        _ => continue,
+                    };
+                    *counter = value.parse().ok()?;
+                    has_counters = true;
+                }
+                None => {
+                    for signal in token.split('|') {
+                        match signal {
+                            "RTS" => stats.rts = true,
+                            "CTS" => stats.cts = true,
+                            "DTR" => stats.dtr = true,
+                            "DSR" => stats.dsr = true,
+                            "CD" => stats.cd = true,
+                            "RI" => stats.ri = true,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        if !has_counters {
+            return None;
+        }
+        return Some((index, stats));
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        #[test]
+        fn test_serial_driver_line_parse() {
+            let (index, stats) =
+                parse_line("0: uart:16550A port:000003F8 irq:4 tx:123 rx:456 fe:1 brk:0 RTS|DTR")
+                    .unwrap();
+            assert_eq!(index, 0);
+            assert_eq!(
+                stats,
+                UartStats {
+                    uart: "16550A".into(),
+                    tx: 123,
+                    rx: 456,
+                    frame_errors: 1,
+                    rts: true,
+                    dtr: true,
+                    ..Default::default()
+                }
+            );
+            assert_eq!(parse_line("serinfo:1.0 driver revision:"), None);
+            assert_eq!(parse_line("1: uart:unknown port:000002F8 irq:3"), None);
+        }
+    }
+}
+
+fn io_error(path: &Path, e: io::Error) -> EnumerationError {
+    match e.kind() {
+        io::ErrorKind::PermissionDenied => EnumerationError::PermissionDenied(path.into()),
+        _ => EnumerationError::Io(path.into(), e),
+    }
+}
+
+/// read /proc/tty/drivers, `Err` if a serial line is malformed,
+/// other malformed lines are skipped
+fn read_tty_drivers(root: &EnumerationRoot) -> Result<Vec<TtyDriver>, EnumerationError> {
+    let tty_drivers = root.procfs.join("tty/drivers");
+    let result = fs::read(&tty_drivers).map_err(|e| io_error(&tty_drivers, e))?;
+    let tty_drivers_info =
+        String::from_utf8(result).map_err(|_| EnumerationError::NonUtf8Attribute(tty_drivers))?;
+    let mut drivers = Vec::new();
+    for line in tty_drivers_info.lines() {
+        match tty_drivers_parser::parse_line(line) {
+            Ok(driver) => drivers.push(driver),
+            Err(e) if line.trim_end().ends_with("serial") => return Err(e),
+            Err(_) => {}
+        }
+    }
+    return Ok(drivers);
+}
+
+fn get_serial_prefix(root: &EnumerationRoot) -> Result<HashMap<String, String>, EnumerationError> {
+    return serial_prefix_of(read_tty_drivers(root));
+}
+
+/// serial prefixes of the tty driver table, or of a fallback table
+/// if the driver table can not be read
+fn serial_prefix_of(
+    tty_drivers: Result<Vec<TtyDriver>, EnumerationError>,
+) -> Result<HashMap<String, String>, EnumerationError> {
+    let mut serial_prefix = HashMap::new();
+
+    match tty_drivers {
+        Ok(tty_drivers) => {
+            for tty_driver in tty_drivers {
+                if tty_driver.kind == TtyDriverKind::Serial {
+                    serial_prefix.insert(tty_driver.prefix, tty_driver.name);
+                }
+            }
+        }
+        // procfs may be restricted, for example by SELinux or on Android
+        Err(EnumerationError::Io(_, _)) | Err(EnumerationError::PermissionDenied(_)) => {
             serial_prefix.insert("ttyS".into(), "serial".into());
             serial_prefix.insert("ttyUSB".into(), "usbserial".into());
             serial_prefix.insert("ttyPS".into(), "other".into());
@@ -67,81 +435,296 @@ fn get_serial_prefix() -> HashMap<String, String> {
             serial_prefix.insert("ttyAMA".into(), "other".into());
             serial_prefix.insert("ttymxc".into(), "other".into());
             serial_prefix.insert("ttyGS".into(), "other".into());
+            serial_prefix.insert("rfcomm".into(), "other".into());
         }
+        Err(e) => return Err(e),
     }
-    return serial_prefix;
+    return Ok(serial_prefix);
 }
 
-fn read_line(path: &PathBuf) -> Option<String> {
+/// read content of an attribute file, `Ok(None)` if it does not exist
+fn read_raw(path: &Path) -> Result<Option<Vec<u8>>, EnumerationError> {
     match fs::read(path) {
         Ok(raw) => {
-            let text = String::from_utf8(raw).expect(path.to_str().unwrap());
-            return text.lines().next().and_then(|l| Some(l.replace("\0", ";")));
+            return Ok(Some(raw));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok(None);
+        }
+        Err(e) => {
+            return Err(io_error(path, e));
+        }
+    }
+}
+
+/// read first line of an attribute file, `Ok(None)` if it does not exist
+fn read_line(path: &Path) -> Result<Option<String>, EnumerationError> {
+    match read_raw(path)? {
+        Some(raw) => {
+            let text = String::from_utf8(raw)
+                .map_err(|_| EnumerationError::NonUtf8Attribute(path.into()))?;
+            return Ok(text.lines().next().map(|l| l.replace('\0', ";")));
         }
-        Err(_) => {
+        None => {
+            return Ok(None);
+        }
+    }
+}
+
+impl SerialInfo {
+    /// record the error of `result` as soft error of the port
+    fn soft_error<T>(&mut self, result: Result<Option<T>, EnumerationError>) -> Option<T> {
+        match result {
+            Ok(value) => return value,
+            Err(e) => {
+                self.errors.push(e);
+                return None;
+            }
+        }
+    }
+
+    /// read first line of an attribute file, errors are recorded as soft errors of the port
+    fn read_attr(&mut self, path: &Path) -> Option<String> {
+        return self.soft_error(read_line(path));
+    }
+
+    /// read content of an attribute file, errors are recorded as soft errors of the port
+    fn read_raw_attr(&mut self, path: &Path) -> Option<Vec<u8>> {
+        return self.soft_error(read_raw(path));
+    }
+}
+
+fn get_file_name(path: &Path) -> Option<String> {
+    return path.file_name().and_then(|s| s.to_str()).map(String::from);
+}
+
+fn get_file_real_name(device_path: &Path, name: &str) -> Option<String> {
+    return fs::canonicalize(device_path.join(name))
+        .ok()
+        .and_then(|real_file_path| get_file_name(&real_file_path));
+}
+
+/// read the driver bound to the device at `real_dev_path`
+fn read_driver_info(real_dev_path: &Path, serial_info: &mut SerialInfo) -> Option<DriverInfo> {
+    // for example /sys/bus/usb-serial/drivers/ftdi_sio
+    let driver_path = fs::canonicalize(real_dev_path.join("driver")).ok()?;
+    let bus = driver_path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(get_file_name);
+    // built-in drivers have no module link, unless built from a module source
+    let module_path = fs::canonicalize(driver_path.join("module")).ok();
+    let (version, srcversion) = match &module_path {
+        Some(module_path) => (
+            serial_info.read_attr(&module_path.join("version")),
+            serial_info.read_attr(&module_path.join("srcversion")),
+        ),
+        None => (None, None),
+    };
+    return Some(DriverInfo {
+        name: get_file_name(&driver_path)?,
+        module: module_path.as_deref().and_then(get_file_name),
+        version,
+        srcversion,
+        bus,
+    });
+}
+
+fn read_usb_location(
+    usb_dev_path: &Path,
+    interface: Option<String>,
+    serial_info: &mut SerialInfo,
+) -> Option<UsbLocation> {
+    let busnum = serial_info.read_attr(&usb_dev_path.join("busnum"))?;
+    let devnum = serial_info.read_attr(&usb_dev_path.join("devnum"))?;
+    return Some(UsbLocation {
+        busnum: busnum.parse().ok()?,
+        devnum: devnum.parse().ok()?,
+        port_path: get_file_name(usb_dev_path)?,
+        interface,
+    });
+}
+
+fn read_usb_link_info(usb_dev_path: &Path, serial_info: &mut SerialInfo) -> UsbLinkInfo {
+    let mut read_attr = |name: &str| {
+        return serial_info
+            .read_attr(&usb_dev_path.join(name))
+            .map(|value| String::from(value.trim()));
+    };
+    let speed = read_attr("speed");
+    let version = read_attr("version");
+    // for example `100mA`, empty if the device is not configured
+    let max_power =
+        read_attr("bMaxPower").and_then(|max_power| max_power.trim_end_matches("mA").parse().ok());
+    let attributes =
+        read_attr("bmAttributes").and_then(|attributes| u8::from_str_radix(&attributes, 16).ok());
+    let removable = read_attr("removable").and_then(|removable| match removable.as_str() {
+        "removable" => Some(true),
+        "fixed" => Some(false),
+        _ => None,
+    });
+    // attribute of the hub port, which is linked from the device
+    let connect_type = read_attr("port/connect_type").map(|connect_type| {
+        return match connect_type.as_str() {
+            "hotplug" => UsbConnectType::Hotplug,
+            "hardwired" => UsbConnectType::Hardwired,
+            "not used" => UsbConnectType::NotUsed,
+            _ => UsbConnectType::Unknown,
+        };
+    });
+    let authorized = read_attr("authorized").map(|authorized| authorized == "1");
+    return UsbLinkInfo {
+        speed,
+        version,
+        max_power,
+        attributes,
+        removable,
+        connect_type,
+        authorized,
+    };
+}
+
+fn read_usb_descriptors(
+    usb_dev_path: &Path,
+    serial_info: &mut SerialInfo,
+) -> Option<UsbDescriptors> {
+    let path = usb_dev_path.join("descriptors");
+    let raw = serial_info.read_raw_attr(&path)?;
+    match UsbDescriptors::parse(&raw) {
+        Ok(descriptors) => return Some(descriptors),
+        Err(e) => {
+            serial_info
+                .errors
+                .push(EnumerationError::MalformedDescriptors(path, e));
             return None;
         }
     }
 }
 
-fn get_file_name(path: &PathBuf) -> Option<String> {
-    return path
-        .file_name()
-        .and_then(|s| s.to_str())
-        .and_then(|s| Some(String::from(s)));
+/// interface descriptor of the port in the active configuration
+fn find_interface_descriptor(
+    usb_dev_path: &Path,
+    descriptors: Option<&UsbDescriptors>,
+    interface_num: Option<u8>,
+    alternate_setting: Option<u8>,
+    serial_info: &mut SerialInfo,
+) -> Option<UsbInterfaceDescriptor> {
+    let descriptors = descriptors?;
+    let interface_num = interface_num?;
+    // empty if the device is not configured
+    let value = serial_info
+        .read_attr(&usb_dev_path.join("bConfigurationValue"))
+        .and_then(|value| value.trim().parse().ok())?;
+    return descriptors
+        .configuration(value)?
+        .interface(interface_num, alternate_setting.unwrap_or(0))
+        .cloned();
 }
 
-fn get_file_real_name(device_path: &PathBuf, name: &str) -> Option<String> {
-    let mut file_path = device_path.clone();
-    file_path.push(name);
-    let real_file_path = fs::canonicalize(&file_path).expect(file_path.to_str().unwrap());
-    return get_file_name(&real_file_path);
+/// fill missing vendor and product strings of devices without string descriptors
+#[cfg(feature = "usb-ids")]
+fn fill_names_from_usb_ids(serial_info: &mut SerialInfo) {
+    if let Some(usb_info) = &serial_info.usb_info {
+        if serial_info.vendor.is_none() {
+            serial_info.vendor = usb_info.vendor_name().map(String::from);
+            serial_info.vendor_from_database = serial_info.vendor.is_some();
+        }
+        if serial_info.product.is_none() {
+            serial_info.product = usb_info.product_name().map(String::from);
+            serial_info.product_from_database = serial_info.product.is_some();
+        }
+    }
 }
 
 fn probe_usb_serial(mut real_dev_path: PathBuf, serial_info: &mut SerialInfo) -> bool {
     let mut interface_num = None;
+    let mut interface = None;
+    let mut interface_id = None;
+    let mut alternate_setting = None;
     for _ in 0..3 {
         // read interface
         if interface_num.is_none() {
             real_dev_path.push("bInterfaceNumber");
-            interface_num = read_line(&real_dev_path);
+            interface_num = serial_info
+                .read_attr(&real_dev_path)
+                .and_then(|num| u8::from_str_radix(&num, 16).ok());
             real_dev_path.pop();
+            // For example: FT2232 with dual port serial
+            real_dev_path.push("interface");
+            interface = serial_info.read_attr(&real_dev_path);
+            real_dev_path.pop();
+            // right aligned decimal, for example ` 0`
+            real_dev_path.push("bAlternateSetting");
+            alternate_setting = serial_info
+                .read_attr(&real_dev_path)
+                .and_then(|setting| setting.trim().parse().ok());
+            real_dev_path.pop();
+            // interface directory is named as <port path>:<config>.<interface>
+            interface_id = get_file_name(&real_dev_path)
+                .and_then(|name| name.split_once(':').map(|(_, id)| String::from(id)));
+            if interface_num.is_some() {
+                // the tty of a usb serial adapter is a child of the interface
+                serial_info.interface_driver = read_driver_info(&real_dev_path, serial_info)
+                    .filter(|driver| Some(driver) != serial_info.driver_info.as_ref());
+            }
         }
 
         // read vendor
         real_dev_path.push("manufacturer");
-        serial_info.vendor = read_line(&real_dev_path);
+        serial_info.vendor = serial_info.read_attr(&real_dev_path);
         real_dev_path.pop();
 
         // read product
         real_dev_path.push("product");
-        serial_info.product = read_line(&real_dev_path).and_then(|mut product| {
-            if let Some(iface_num) = &interface_num {
-                // For example: FT2232 with dual port serial
-                product.push(':');
-                product.push_str(&iface_num);
-                return Some(product);
-            }
-            Some(product)
-        });
+        serial_info.product = serial_info.read_attr(&real_dev_path);
         real_dev_path.pop();
         // read vid and pid
         real_dev_path.push("idVendor");
-        let vid = read_line(&real_dev_path);
+        let vid = serial_info.read_attr(&real_dev_path);
         real_dev_path.pop();
         real_dev_path.push("idProduct");
-        let pid = read_line(&real_dev_path);
+        let pid = serial_info.read_attr(&real_dev_path);
         real_dev_path.pop();
         if let (Some(vid), Some(pid)) = (vid, pid) {
-            serial_info.usb_info = Some(UsbInfo { vid, pid });
+            real_dev_path.push("serial");
+            let serial_number = serial_info.read_attr(&real_dev_path);
+            real_dev_path.pop();
+            real_dev_path.push("bcdDevice");
+            let bcd_device = serial_info.read_attr(&real_dev_path);
+            real_dev_path.pop();
+            let location = read_usb_location(&real_dev_path, interface_id.take(), serial_info);
+            let link = read_usb_link_info(&real_dev_path, serial_info);
+            let descriptors = read_usb_descriptors(&real_dev_path, serial_info);
+            let interface_descriptor = find_interface_descriptor(
+                &real_dev_path,
+                descriptors.as_ref(),
+                interface_num,
+                alternate_setting,
+                serial_info,
+            );
+            serial_info.usb_info = Some(UsbInfo {
+                vid,
+                pid,
+                serial_number,
+                bcd_device,
+                interface_number: interface_num,
+                interface: interface.take(),
+                location,
+                link: Some(link),
+                descriptors,
+                interface_descriptor,
+            });
+            #[cfg(feature = "usb-ids")]
+            fill_names_from_usb_ids(serial_info);
         }
 
         if serial_info.vendor.is_none()
             && serial_info.product.is_none()
             && serial_info.usb_info.is_none()
         {
-            real_dev_path.push("../");
+            real_dev_path.pop();
         } else {
+            serial_info.kind = PortKind::Usb;
             return true;
         }
     }
@@ -150,16 +733,249 @@ fn probe_usb_serial(mut real_dev_path: PathBuf, serial_info: &mut SerialInfo) ->
 
 fn probe_acm_serial(mut real_dev_path: PathBuf, serial_info: &mut SerialInfo) -> bool {
     real_dev_path.push("subsystem");
-    let dev_subsystem = fs::canonicalize(&real_dev_path).expect(real_dev_path.to_str().unwrap());
+    let dev_subsystem = fs::canonicalize(&real_dev_path);
     real_dev_path.pop();
 
-    if dev_subsystem.ends_with("usb") {
-        return probe_usb_serial(real_dev_path, serial_info);
+    if dev_subsystem.is_ok_and(|subsystem| subsystem.ends_with("usb")) {
+        let is_usb_serial = probe_usb_serial(real_dev_path.clone(), serial_info);
+        serial_info.acm_info = read_acm_info(&real_dev_path, serial_info);
+        return is_usb_serial;
     }
     return true;
 }
 
-fn probe_builtin_serial(mut real_dev_path: PathBuf, serial_info: &mut SerialInfo) -> bool {
+/// fill interfaces and country codes from the CDC functional descriptors
+fn decode_acm_descriptors(interface_descriptor: &UsbInterfaceDescriptor, acm_info: &mut AcmInfo) {
+    // bDescriptorSubtype of CDC functional descriptors
+    const CALL_MANAGEMENT: u8 = 0x01;
+    const ACM: u8 = 0x02;
+    const UNION: u8 = 0x06;
+    const COUNTRY_SELECTION: u8 = 0x07;
+
+    if let Some(&[capabilities, ..]) = interface_descriptor.functional_descriptor(ACM) {
+        acm_info.capabilities = capabilities;
+    }
+    if let Some(&[_, data_interface, ..]) =
+        interface_descriptor.functional_descriptor(CALL_MANAGEMENT)
+    {
+        acm_info.data_interface = Some(data_interface);
+    }
+    // the union descriptor takes precedence, the cdc_acm driver uses it for pairing
+    if let Some(&[control_interface, data_interface, ..]) =
+        interface_descriptor.functional_descriptor(UNION)
+    {
+        acm_info.control_interface = Some(control_interface);
+        acm_info.data_interface = Some(data_interface);
+    }
+    if let Some(&[release_index, ref country_codes @ ..]) =
+        interface_descriptor.functional_descriptor(COUNTRY_SELECTION)
+    {
+        acm_info.country_code_release_index = Some(release_index);
+        acm_info.country_codes = country_codes
+            .chunks_exact(2)
+            .map(|code| u16::from_le_bytes([code[0], code[1]]))
+            .collect();
+    }
+}
+
+/// read CDC-ACM information of the communication interface at `interface_path`
+fn read_acm_info(interface_path: &Path, serial_info: &mut SerialInfo) -> Option<AcmInfo> {
+    // hex, after quirks of the cdc_acm driver are applied
+    let capabilities = serial_info
+        .read_attr(&interface_path.join("bmCapabilities"))
+        .and_then(|capabilities| u8::from_str_radix(capabilities.trim(), 16).ok());
+    let usb_info = serial_info.usb_info.as_ref()?;
+    let mut acm_info = AcmInfo {
+        control_interface: usb_info.interface_number,
+        ..Default::default()
+    };
+    match &usb_info.interface_descriptor {
+        Some(interface_descriptor) => decode_acm_descriptors(interface_descriptor, &mut acm_info),
+        None if capabilities.is_none() => return None,
+        None => {}
+    }
+    if let Some(capabilities) = capabilities {
+        acm_info.capabilities = capabilities;
+    }
+    return Some(acm_info);
+}
+
+fn find_pci_device(real_dev_path: &Path) -> Option<PathBuf> {
+    return real_dev_path
+        .ancestors()
+        .take(2)
+        .find(|path| {
+            fs::canonicalize(path.join("subsystem"))
+                .is_ok_and(|subsystem| subsystem.ends_with("pci"))
+        })
+        .map(PathBuf::from);
+}
+
+/// read a hex id attribute of a pci device, without the `0x` prefix
+fn read_pci_id(pci_dev_path: &Path, name: &str, serial_info: &mut SerialInfo) -> Option<String> {
+    return serial_info
+        .read_attr(&pci_dev_path.join(name))
+        .map(|id| String::from(id.trim_start_matches("0x")));
+}
+
+fn read_pci_info(pci_dev_path: &Path, serial_info: &mut SerialInfo) -> Option<PciInfo> {
+    return Some(PciInfo {
+        vendor: read_pci_id(pci_dev_path, "vendor", serial_info)?,
+        device: read_pci_id(pci_dev_path, "device", serial_info)?,
+        subsystem_vendor: read_pci_id(pci_dev_path, "subsystem_vendor", serial_info),
+        subsystem_device: read_pci_id(pci_dev_path, "subsystem_device", serial_info),
+        class: read_pci_id(pci_dev_path, "class", serial_info),
+        slot: get_file_name(pci_dev_path)?,
+    });
+}
+
+/// parse a number of sysfs, hex if prefixed with `0x`
+fn parse_number(number: &str) -> Option<u64> {
+    return match number.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => number.parse().ok(),
+    };
+}
+
+/// read a number attribute of sysfs
+fn read_number(path: &Path, serial_info: &mut SerialInfo) -> Option<u64> {
+    return serial_info
+        .read_attr(path)
+        .and_then(|number| parse_number(&number));
+}
+
+/// legacy port of serial core without hardware
+fn is_phantom_uart(uart_info: &UartInfo) -> bool {
+    // PORT_UNKNOWN, no UART was detected at the port, SPI and I2C bridges such as
+    // sc16is7xx report `port` and `iomem_base` 0 and must not be dropped
+    return uart_info.uart_type == 0;
+}
+
+/// read the UART of a serial core port at `tty_path`, `None` for ttys of other drivers
+fn read_uart_info(
+    real_dev_path: &Path,
+    tty_path: &Path,
+    serial_info: &mut SerialInfo,
+) -> Option<UartInfo> {
+    let uart_type = read_number(&tty_path.join("type"), serial_info)? as u32;
+    return Some(UartInfo {
+        uart_type,
+        port: read_number(&tty_path.join("port"), serial_info),
+        iomem_base: read_number(&tty_path.join("iomem_base"), serial_info),
+        irq: read_number(&tty_path.join("irq"), serial_info).map(|irq| irq as u32),
+        uartclk: read_number(&tty_path.join("uartclk"), serial_info),
+        xmit_fifo_size: read_number(&tty_path.join("xmit_fifo_size"), serial_info)
+            .map(|size| size as u32),
+        io_type: read_number(&tty_path.join("io_type"), serial_info).map(|io| io as u32),
+        close_delay: read_number(&tty_path.join("close_delay"), serial_info)
+            .map(|delay| delay as u32),
+        flags: read_number(&tty_path.join("flags"), serial_info),
+        platform_device: get_file_name(real_dev_path),
+    });
+}
+
+/// split a string list property of device tree, strings are terminated by NUL
+fn split_dt_strings(raw: &[u8]) -> Vec<String> {
+    return raw
+        .split(|c| *c == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect();
+}
+
+/// split a property of device tree into cells, cells are big endian u32
+fn split_dt_cells(raw: &[u8]) -> Vec<u32> {
+    return raw
+        .chunks_exact(4)
+        .map(|cell| u32::from_be_bytes(cell.try_into().unwrap()))
+        .collect();
+}
+
+/// read RS-485 properties of the device tree node, `None` if the node has none of them
+fn read_rs485_config(real_dev_path: &Path, serial_info: &mut SerialInfo) -> Option<Rs485Config> {
+    let of_node = real_dev_path.join("of_node");
+    let rts_delay = serial_info
+        .read_raw_attr(&of_node.join("rs485-rts-delay"))
+        .map(|raw| split_dt_cells(&raw));
+    let rs485_config = Rs485Config {
+        enabled_at_boot_time: of_node.join("linux,rs485-enabled-at-boot-time").exists(),
+        rts_delay_before_send: rts_delay.as_ref().and_then(|d| d.first().copied()),
+        rts_delay_after_send: rts_delay.as_ref().and_then(|d| d.get(1).copied()),
+        rts_active_low: of_node.join("rs485-rts-active-low").exists(),
+        rx_during_tx: of_node.join("rs485-rx-during-tx").exists(),
+        rts_gpios: false,
+        term_gpios: of_node.join("rs485-term-gpios").exists(),
+    };
+    // `rts-gpios` alone is the RTS line of an RS-232 port
+    if rs485_config == Rs485Config::default() {
+        return None;
+    }
+    return Some(Rs485Config {
+        rts_gpios: of_node.join("rts-gpios").exists(),
+        ..rs485_config
+    });
+}
+
+fn read_device_tree_info(
+    root: &EnumerationRoot,
+    real_dev_path: &Path,
+    serial_info: &mut SerialInfo,
+) -> Option<DeviceTreeInfo> {
+    let of_node = fs::canonicalize(real_dev_path.join("of_node")).ok()?;
+    let base = fs::canonicalize(root.sysfs.join("firmware/devicetree/base")).ok()?;
+    let path = format!("/{}", of_node.strip_prefix(base).ok()?.to_string_lossy());
+    let compatible = serial_info
+        .read_raw_attr(&of_node.join("compatible"))
+        .map(|raw| split_dt_strings(&raw))
+        .unwrap_or_default();
+    let status = serial_info
+        .read_raw_attr(&of_node.join("status"))
+        .and_then(|raw| split_dt_strings(&raw).into_iter().next());
+    let current_speed = serial_info
+        .read_raw_attr(&of_node.join("current-speed"))
+        .and_then(|raw| split_dt_cells(&raw).first().copied());
+    return Some(DeviceTreeInfo {
+        path,
+        compatible,
+        status,
+        current_speed,
+        aliases: Vec::new(),
+    });
+}
+
+fn resolve_device_tree_aliases(root: &EnumerationRoot, serial_list: &mut [SerialInfo]) {
+    let entries = match fs::read_dir(root.procfs.join("device-tree/aliases")) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut aliases = Vec::new();
+    for entry in entries.flatten() {
+        if let (Some(name), Ok(raw)) = (entry.file_name().to_str(), fs::read(entry.path())) {
+            if let Some(path) = split_dt_strings(&raw).into_iter().next() {
+                aliases.push((String::from(name), path));
+            }
+        }
+    }
+    aliases.sort();
+    for serial_info in serial_list {
+        if let Some(device_tree) = &mut serial_info.device_tree {
+            device_tree.aliases = aliases
+                .iter()
+                .filter(|(_, path)| *path == device_tree.path)
+                .map(|(name, _)| name.clone())
+                .collect();
+        }
+    }
+}
+
+fn probe_builtin_serial(
+    root: &EnumerationRoot,
+    mut real_dev_path: PathBuf,
+    tty_path: &Path,
+    serial_info: &mut SerialInfo,
+) -> bool {
+    serial_info.uart_info = read_uart_info(&real_dev_path, tty_path, serial_info);
+
     // declared in device tree
     real_dev_path.push("of_node");
     let is_exist_ofnode = real_dev_path.exists();
@@ -174,64 +990,2043 @@ fn probe_builtin_serial(mut real_dev_path: PathBuf, serial_info: &mut SerialInfo
         serial_info.vendor = get_file_real_name(&real_dev_path, "subsystem");
 
         if is_exist_ofnode {
+            serial_info.device_tree = read_device_tree_info(root, &real_dev_path, serial_info);
+            serial_info.rs485 = read_rs485_config(&real_dev_path, serial_info);
             // compatible property of device tree
             real_dev_path.push("of_node/compatible");
         } else {
             // pnp id
             real_dev_path.push("id");
         }
-        serial_info.product = read_line(&real_dev_path);
+        serial_info.product = serial_info.read_attr(&real_dev_path);
+        serial_info.kind = PortKind::Builtin;
+        return true;
+    }
+
+    // port of a pci serial card, or of a platform device on a pci function such as Intel LPSS
+    if let Some(pci_dev_path) = find_pci_device(&real_dev_path) {
+        serial_info.pci_info = read_pci_info(&pci_dev_path, serial_info);
+        if serial_info.pci_info.is_some() {
+            // the ids are in `pci_info`, there is no name source for the product
+            serial_info.vendor = get_file_real_name(&pci_dev_path, "subsystem");
+            serial_info.kind = PortKind::Pci;
+            return true;
+        }
+    }
+
+    // legacy port of serial core without firmware description, such as of serial8250
+    if serial_info.uart_info.is_some() {
+        serial_info.vendor = get_file_real_name(&real_dev_path, "subsystem");
+        serial_info.product = get_file_name(&real_dev_path);
+        serial_info.kind = PortKind::Builtin;
         return true;
     }
     return false;
 }
 
+/// name of the hci adapter of the tty device, a connected port is moved below
+/// the ACL connection, for example `hci0/hci0:256`
+fn find_bluetooth_adapter(device_path: &Path) -> Option<String> {
+    return device_path
+        .ancestors()
+        .take(2)
+        .filter_map(get_file_name)
+        .find(|name| name.starts_with("hci") && !name.contains(':'));
+}
+
+/// probe a rfcomm tty, which is only bound to the adapter while connected,
+/// address and channel are attributes of the tty
+fn probe_bluetooth_serial(
+    root: &EnumerationRoot,
+    tty_path: &Path,
+    serial_info: &mut SerialInfo,
+) -> bool {
+    let mut bluetooth_info = BluetoothInfo {
+        address: serial_info.read_attr(&tty_path.join("address")),
+        channel: read_number(&tty_path.join("channel"), serial_info).map(|ch| ch as u8),
+        adapter: fs::canonicalize(tty_path.join("device"))
+            .ok()
+            .and_then(|device| find_bluetooth_adapter(&device)),
+    };
+
+    if bluetooth_info.adapter.is_none() {
+        // without a connection, the adapter is only known if there is a single one
+        let adapters: Vec<_> = fs::read_dir(root.sysfs.join("class/bluetooth"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with("hci") && !name.contains(':'))
+            .collect();
+        if adapters.len() == 1 {
+            bluetooth_info.adapter = adapters.into_iter().next();
+        }
+    }
+
+    serial_info.kind = PortKind::Bluetooth;
+    serial_info.bluetooth_info = Some(bluetooth_info);
+    return true;
+}
+
+/// find the configfs function instance of the gadget serial port `port_num`
+fn find_gadget_function(
+    gadgets_path: &Path,
+    port_num: &str,
+    serial_info: &mut SerialInfo,
+) -> Option<(PathBuf, String)> {
+    // function types of f_acm, f_serial and f_obex which are backed by u_serial
+    const SERIAL_FUNCTIONS: [&str; 3] = ["acm", "gser", "obex"];
+    for gadget in fs::read_dir(gadgets_path).into_iter().flatten().flatten() {
+        let functions = fs::read_dir(gadget.path().join("functions"));
+        for function in functions.into_iter().flatten().flatten() {
+            let function_name = match function.file_name().into_string() {
+                Ok(function_name) => function_name,
+                Err(_) => continue,
+            };
+            let is_serial_function = function_name
+                .split_once('.')
+                .is_some_and(|(kind, _)| SERIAL_FUNCTIONS.contains(&kind));
+            if is_serial_function
+                && serial_info
+                    .read_attr(&function.path().join("port_num"))
+                    .as_deref()
+                    == Some(port_num)
+            {
+                return Some((gadget.path(), function_name));
+            }
+        }
+    }
+    return None;
+}
+
+fn probe_gadget_serial(
+    root: &EnumerationRoot,
+    tty_name: &str,
+    serial_info: &mut SerialInfo,
+) -> bool {
+    let port_num = &tty_name["ttyGS".len()..];
+    let mut gadget_info = GadgetInfo::default();
+    let gadgets_path = root.sysfs.join("kernel/config/usb_gadget");
+    if let Some((gadget_path, function)) =
+        find_gadget_function(&gadgets_path, port_num, serial_info)
+    {
+        let strings_path = gadget_path.join("strings/0x409");
+        serial_info.vendor = serial_info.read_attr(&strings_path.join("manufacturer"));
+        serial_info.product = serial_info.read_attr(&strings_path.join("product"));
+        // an unbound gadget has an empty `UDC` attribute
+        gadget_info.udc = serial_info
+            .read_attr(&gadget_path.join("UDC"))
+            .filter(|udc| !udc.is_empty());
+        let in_config = fs::read_dir(gadget_path.join("configs"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .any(|config| config.path().join(&function).exists());
+        if let Some(udc) = gadget_info.udc.clone() {
+            let state =
+                serial_info.read_attr(&root.sysfs.join("class/udc").join(udc).join("state"));
+            gadget_info.configured = in_config && state.as_deref() == Some("configured");
+        }
+        gadget_info.gadget = get_file_name(&gadget_path);
+        gadget_info.function = Some(function);
+    }
+    serial_info.driver = Some("g_serial".into());
+    serial_info.kind = PortKind::Gadget;
+    serial_info.gadget_info = Some(gadget_info);
+    return true;
+}
+
+/// read the `major:minor` device number of the tty at `tty_path` in sysfs
+fn read_device_number(tty_path: &Path, serial_info: &mut SerialInfo) -> Option<DeviceNumber> {
+    let dev_path = tty_path.join("dev");
+    let dev = serial_info.read_attr(&dev_path)?;
+    let device_number = dev.split_once(':').and_then(|(major, minor)| {
+        return Some(DeviceNumber {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        });
+    });
+    if device_number.is_none() {
+        let e = io::Error::new(io::ErrorKind::InvalidData, "malformed device number");
+        serial_info.errors.push(io_error(&dev_path, e));
+    }
+    return device_number;
+}
+
+/// device number of a character device node, `None` for other files
+fn get_node_device_number(metadata: &fs::Metadata) -> Option<DeviceNumber> {
+    if !metadata.file_type().is_char_device() {
+        return None;
+    }
+    // dev_t encoding of glibc and the kernel's new_encode_dev
+    let rdev = metadata.rdev();
+    return Some(DeviceNumber {
+        major: (((rdev >> 32) & 0xffff_f000) | ((rdev >> 8) & 0xfff)) as u32,
+        minor: (((rdev >> 12) & 0xffff_ff00) | (rdev & 0xff)) as u32,
+    });
+}
+
+#[derive(Default)]
+/// character device nodes and symlinks below devfs
+struct DevfsEntries {
+    /// character device nodes by their device number
+    nodes: HashMap<DeviceNumber, PathBuf>,
+    /// symlinks by the canonical path of their target
+    links: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+/// collect the device nodes and symlinks below devfs in a single walk
+fn scan_devfs(root: &EnumerationRoot) -> DevfsEntries {
+    // virtual filesystems mounted below /dev which never contain serial ports
+    const SKIPPED_DIRS: [&str; 4] = ["pts", "shm", "mqueue", "hugepages"];
+    let mut devfs_entries = DevfsEntries::default();
+    let mut dirs = vec![root.devfs.clone()];
+    while let Some(dir) = dirs.pop() {
+        let mut entries: Vec<_> = match fs::read_dir(&dir) {
+            Ok(entries) => entries.flatten().collect(),
+            Err(_) => continue,
+        };
+        // prefer the first node in name order if there are several
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+            let path = entry.path();
+            if file_type.is_symlink() {
+                scan_link(root, path, &mut devfs_entries.links);
+            } else if file_type.is_char_device() {
+                if let Some(device_number) = fs::symlink_metadata(&path)
+                    .ok()
+                    .and_then(|metadata| get_node_device_number(&metadata))
+                {
+                    devfs_entries.nodes.entry(device_number).or_insert(path);
+                }
+            } else if file_type.is_dir()
+                && !SKIPPED_DIRS
+                    .iter()
+                    .any(|skipped| entry.file_name() == *skipped)
+            {
+                dirs.push(path);
+            }
+        }
+    }
+    return devfs_entries;
+}
+
+/// add the symlink `path` to `links` by the canonical path of its target
+fn scan_link(root: &EnumerationRoot, path: PathBuf, links: &mut HashMap<PathBuf, Vec<PathBuf>>) {
+    // `/dev/stdin` and the like point to `/proc/self/fd`, the tty of the calling process
+    let is_proc_link = fs::read_link(&path)
+        .is_ok_and(|target| target.starts_with("/proc") || target.starts_with(&root.procfs));
+    if is_proc_link {
+        return;
+    }
+    if let Ok(target) = fs::canonicalize(&path) {
+        links.entry(target).or_default().push(path);
+    }
+}
+
+/// mark ports used as kernel console, before the names are replaced by the device nodes
+fn resolve_consoles(root: &EnumerationRoot, serial_list: &mut [SerialInfo]) {
+    let mut consoles: HashMap<String, ConsoleInfo> = HashMap::new();
+    if let Ok(text) = fs::read_to_string(root.procfs.join("consoles")) {
+        for (name, flags) in text.lines().filter_map(consoles_parser::parse_line) {
+            let console = consoles.entry(name).or_default();
+            console.active |= flags.contains('E');
+            console.preferred |= flags.contains('C');
+        }
+    }
+    if let Ok(text) = fs::read_to_string(root.sysfs.join("class/tty/console/active")) {
+        for name in text.split_whitespace() {
+            consoles.entry(name.into()).or_default().active = true;
+        }
+    }
+    if let Ok(text) = fs::read_to_string(root.procfs.join("cmdline")) {
+        for console in text
+            .split_whitespace()
+            .filter_map(|arg| arg.strip_prefix("console="))
+        {
+            let (name, options) = match console.split_once(',') {
+                Some((name, options)) => (name, Some(options.to_string())),
+                None => (console, None),
+            };
+            let name = name.strip_prefix("/dev/").unwrap_or(name);
+            consoles.entry(name.into()).or_default().options = options;
+        }
+    }
+
+    for serial_info in serial_list {
+        let name = match Path::new(&serial_info.name).file_name() {
+            Some(name) => name.to_owned(),
+            None => continue,
+        };
+        serial_info.console = name.to_str().and_then(|name| consoles.remove(name));
+    }
+}
+
+/// attach the status of `/proc/tty/driver/serial` to the `ttyS` ports,
+/// before the names are replaced by the device nodes
+fn resolve_uart_stats(root: &EnumerationRoot, serial_list: &mut [SerialInfo]) {
+    let serial_list: Vec<(u32, &mut SerialInfo)> = serial_list
+        .iter_mut()
+        .filter_map(|serial_info| {
+            let name = Path::new(&serial_info.name).file_name()?.to_str()?;
+            let line = name.strip_prefix("ttyS")?.parse().ok()?;
+            return Some((line, serial_info));
+        })
+        .collect();
+    if serial_list.is_empty() {
+        return;
+    }
+    let serial_driver = root.procfs.join("tty/driver/serial");
+    match fs::read_to_string(&serial_driver) {
+        Ok(text) => {
+            let mut uart_stats: HashMap<_, _> = text
+                .lines()
+                .filter_map(serial_driver_parser::parse_line)
+                .collect();
+            for (line, serial_info) in serial_list {
+                serial_info.uart_stats = uart_stats.remove(&line);
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => {
+            // only readable by root, report it for each port which misses the status
+            for (_, serial_info) in serial_list {
+                let e = io::Error::new(e.kind(), e.to_string());
+                serial_info.errors.push(io_error(&serial_driver, e));
+            }
+        }
+    }
+}
+
+/// find the device node of each port by its device number,
+/// the node may be renamed by udev rules or missing, for example in a container
+fn resolve_device_nodes(nodes: &HashMap<DeviceNumber, PathBuf>, serial_list: &mut [SerialInfo]) {
+    for serial_info in serial_list {
+        let device_number = match serial_info.device_number {
+            Some(device_number) if serial_info.device_node.is_none() => device_number,
+            _ => continue,
+        };
+        let kernel_node = PathBuf::from(&serial_info.name);
+        let is_kernel_node = fs::symlink_metadata(&kernel_node)
+            .is_ok_and(|metadata| get_node_device_number(&metadata) == Some(device_number));
+        serial_info.device_node = if is_kernel_node {
+            Some(kernel_node)
+        } else {
+            nodes.get(&device_number).cloned()
+        };
+        if let Some(device_node) = &serial_info.device_node {
+            serial_info.name = device_node.to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// read the udev database record of the port
+fn read_udev_info(root: &EnumerationRoot, serial_info: &mut SerialInfo) -> Option<UdevInfo> {
+    let udev_db = root
+        .run
+        .join(format!("udev/data/c{}", serial_info.device_number?));
+    match fs::read_to_string(&udev_db) {
+        Ok(text) => return Some(udev_db_parser::parse(&text)),
+        // udev is not running, for example in a container
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            serial_info.errors.push(io_error(&udev_db, e));
+            return None;
+        }
+    }
+}
+
 fn probe_serial_by_prefix(
+    options: &EnumerationOptions,
     serial_list: &mut Vec<SerialInfo>,
     serial_prefix: &HashMap<String, String>,
-) {
-    const TTY_DEVICE_PATH: &str = "/sys/class/tty";
-    for _entry in fs::read_dir(TTY_DEVICE_PATH).unwrap() {
-        if let Ok(entry) = _entry {
-            let _file_name = entry.file_name();
-            let file_name = _file_name.to_str().expect(format!("{:?}", entry).as_str());
-            for (prefix, driver_class) in serial_prefix {
-                if file_name.starts_with(prefix) {
-                    let mut device_path = entry.path();
-                    device_path.push("device");
-                    let real_dev_path =
-                        fs::canonicalize(&device_path).expect(device_path.to_str().unwrap());
-                    let mut serial_info = SerialInfo {
-                        name: format!("/dev/{}", file_name),
-                        vendor: None,
-                        product: None,
-                        driver: get_file_real_name(&real_dev_path, "driver"),
-                        usb_info: None,
-                    };
-                    if real_dev_path.exists() {
-                        let is_valid_serial = if file_name.starts_with("ttyACM") {
-                            probe_acm_serial(real_dev_path, &mut serial_info)
-                        } else {
-                            match driver_class.as_str() {
-                                "usbserial" => probe_usb_serial(real_dev_path, &mut serial_info),
-                                _ => probe_builtin_serial(real_dev_path, &mut serial_info),
-                            }
-                        };
-                        if is_valid_serial {
-                            serial_list.push(serial_info);
+) -> Result<(), EnumerationError> {
+    let root = &options.root;
+    let tty_device_path = root.sysfs.join("class/tty");
+    let entries = fs::read_dir(&tty_device_path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => EnumerationError::SysfsMissing(tty_device_path.clone()),
+        _ => io_error(&tty_device_path, e),
+    })?;
+    for entry in entries.flatten() {
+        let _file_name = entry.file_name();
+        let file_name = match _file_name.to_str() {
+            Some(file_name) => file_name,
+            None => continue,
+        };
+        for (prefix, driver_class) in serial_prefix {
+            if file_name.starts_with(prefix) {
+                let mut serial_info = SerialInfo {
+                    name: root.devfs.join(file_name).to_string_lossy().into_owned(),
+                    ..Default::default()
+                };
+                let mut device_path = entry.path();
+                device_path.push("device");
+                let is_valid_serial = if file_name.starts_with("rfcomm") {
+                    probe_bluetooth_serial(root, &entry.path(), &mut serial_info)
+                } else if file_name.starts_with("ttyGS") {
+                    probe_gadget_serial(root, file_name, &mut serial_info)
+                } else if let Ok(real_dev_path) = fs::canonicalize(&device_path) {
+                    serial_info.driver = get_file_real_name(&real_dev_path, "driver");
+                    serial_info.driver_info = read_driver_info(&real_dev_path, &mut serial_info);
+                    if file_name.starts_with("ttyACM") {
+                        probe_acm_serial(real_dev_path, &mut serial_info)
+                    } else {
+                        match driver_class.as_str() {
+                            "usbserial" => probe_usb_serial(real_dev_path, &mut serial_info),
+                            _ => probe_builtin_serial(
+                                root,
+                                real_dev_path,
+                                &entry.path(),
+                                &mut serial_info,
+                            ),
                         }
                     }
-                    break;
+                } else {
+                    // tty without backing device, such as a virtual console
+                    false
+                };
+                let is_phantom_serial = !options.include_phantom_ports
+                    && serial_info.uart_info.as_ref().is_some_and(is_phantom_uart);
+                if is_valid_serial && !is_phantom_serial {
+                    serial_info.device_number = read_device_number(&entry.path(), &mut serial_info);
+                    serial_info.udev = read_udev_info(root, &mut serial_info);
+                    serial_list.push(serial_info);
                 }
+                break;
             }
         }
     }
+    return Ok(());
 }
 
-/// enumerate all avaliable serial port
+fn resolve_links(
+    options: &EnumerationOptions,
+    mut dev_links: HashMap<PathBuf, Vec<PathBuf>>,
+    serial_list: &mut [SerialInfo],
+) {
+    for dir in &options.virtual_link_dirs {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            if entry
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_symlink())
+            {
+                scan_link(&options.root, entry.path(), &mut dev_links);
+            }
+        }
+    }
+    for serial_info in serial_list {
+        if let Ok(node) = fs::canonicalize(&serial_info.name) {
+            if let Some(mut links) = dev_links.remove(&node) {
+                links.sort();
+                serial_info.links = links;
+            }
+        }
+    }
+}
+
+/// probe ports without backing hardware
+fn probe_virtual_serial(
+    options: &EnumerationOptions,
+    serial_list: &mut Vec<SerialInfo>,
+    serial_prefix: &HashMap<String, String>,
+) {
+    // tty0tty null-modem pairs, ttyV pairs and the printk tty
+    const VIRTUAL_PREFIXES: [&str; 3] = ["tnt", "ttyV", "ttyprintk"];
+    let root = &options.root;
+
+    if let Ok(entries) = fs::read_dir(root.sysfs.join("class/tty")) {
+        for entry in entries.flatten() {
+            let file_name = match entry.file_name().into_string() {
+                Ok(file_name) => file_name,
+                Err(_) => continue,
+            };
+            let is_virtual_serial = VIRTUAL_PREFIXES
+                .iter()
+                .copied()
+                .chain(serial_prefix.keys().map(String::as_str))
+                .any(|prefix| file_name.starts_with(prefix))
+                && !file_name.starts_with("rfcomm")
+                && !file_name.starts_with("ttyGS")
+                && !entry.path().join("device").exists();
+            if is_virtual_serial {
+                let mut serial_info = SerialInfo {
+                    name: root.devfs.join(&file_name).to_string_lossy().into_owned(),
+                    kind: PortKind::Virtual,
+                    ..Default::default()
+                };
+                serial_info.device_number = read_device_number(&entry.path(), &mut serial_info);
+                serial_list.push(serial_info);
+            }
+        }
+    }
+
+    // pseudo terminals, for example of simulators or socat
+    if let Ok(entries) = fs::read_dir(root.devfs.join("pts")) {
+        for entry in entries.flatten() {
+            let is_pty = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.bytes().all(|c| c.is_ascii_digit()));
+            if is_pty {
+                let device_number = entry
+                    .metadata()
+                    .ok()
+                    .and_then(|metadata| get_node_device_number(&metadata));
+                serial_list.push(SerialInfo {
+                    name: entry.path().to_string_lossy().into_owned(),
+                    device_number,
+                    device_node: device_number.map(|_| entry.path()),
+                    driver: Some("pty_slave".into()),
+                    kind: PortKind::Virtual,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+}
+
+/// effective user and groups of the current process
+struct Credentials {
+    uid: u32,
+    user: Option<String>,
+    gids: Vec<u32>,
+}
+
+impl Credentials {
+    fn current(passwd: &str) -> Credentials {
+        let uid = unsafe { libc::geteuid() };
+        let mut gids = vec![unsafe { libc::getegid() }];
+        let count = unsafe { libc::getgroups(0, std::ptr::null_mut()) };
+        if count > 0 {
+            let mut groups = vec![0; count as usize];
+            let count = unsafe { libc::getgroups(count, groups.as_mut_ptr()) };
+            groups.truncate(count.max(0) as usize);
+            gids.extend(groups);
+        }
+        return Credentials {
+            uid,
+            user: lookup_account_name(passwd, uid),
+            gids,
+        };
+    }
+}
+
+/// name of the id `id` in the content of `/etc/passwd` or `/etc/group`
+fn lookup_account_name(db: &str, id: u32) -> Option<String> {
+    return db
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.get(2).and_then(|field| field.parse().ok()) == Some(id))
+        .map(|fields| fields[0].to_string());
+}
+
+/// members of the group `gid` in the content of `/etc/group`
+fn lookup_group_members(group_db: &str, gid: u32) -> Vec<&str> {
+    return group_db
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.get(2).and_then(|field| field.parse().ok()) == Some(gid))
+        .and_then(|fields| fields.get(3).copied())
+        .map(|members| members.split(',').filter(|m| !m.is_empty()).collect())
+        .unwrap_or_default();
+}
+
+/// explain why a node of `access` can not be opened with `credentials`
+fn find_access_problems(
+    access: &PortAccess,
+    credentials: &Credentials,
+    group_members: &[&str],
+    has_acl: bool,
+) -> Vec<AccessProblem> {
+    let uid = access.uid.unwrap_or_default();
+    let gid = access.gid.unwrap_or_default();
+    let mode = access.mode.unwrap_or_default();
+    let in_group = credentials.gids.contains(&gid);
+    let mut problems = Vec::new();
+    if has_acl {
+        problems.push(AccessProblem::AclMissing);
+    }
+    if credentials.uid != uid && !in_group && mode & 0o060 == 0o060 {
+        let group = access.group.clone().unwrap_or_else(|| gid.to_string());
+        let is_member = credentials
+            .user
+            .as_deref()
+            .is_some_and(|user| group_members.contains(&user));
+        if is_member {
+            problems.push(AccessProblem::GroupNotActive(group));
+        } else {
+            problems.push(AccessProblem::NotInGroup(group));
+        }
+    }
+    let granted = if credentials.uid == uid {
+        mode & 0o600 == 0o600
+    } else if in_group {
+        mode & 0o060 == 0o060
+    } else {
+        mode & 0o006 == 0o006
+    };
+    if problems.is_empty() && !granted {
+        problems.push(AccessProblem::ModeDenied);
+    }
+    return problems;
+}
+
+impl SerialInfo {
+    /// linux only, ownership and permissions of the device node,
+    /// and why the current process can not open it for read and write
+    pub fn access(&self) -> PortAccess {
+        return self.access_from(&EnumerationRoot::default());
+    }
+
+    /// linux only, [`SerialInfo::access`] with the `passwd` and `group` databases
+    /// below `root`
+    pub fn access_from(&self, root: &EnumerationRoot) -> PortAccess {
+        let path = match &self.device_node {
+            Some(device_node) => device_node.clone(),
+            None => PathBuf::from(&self.name),
+        };
+        let mut access = PortAccess {
+            path,
+            ..Default::default()
+        };
+        let metadata = match fs::metadata(&access.path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                access.problems.push(AccessProblem::NodeMissing);
+                return access;
+            }
+            Err(e) => {
+                access.problems.push(AccessProblem::Other(e.to_string()));
+                return access;
+            }
+        };
+        let passwd = fs::read_to_string(root.etc.join("passwd")).unwrap_or_default();
+        let group_db = fs::read_to_string(root.etc.join("group")).unwrap_or_default();
+        access.uid = Some(metadata.uid());
+        access.owner = lookup_account_name(&passwd, metadata.uid());
+        access.gid = Some(metadata.gid());
+        access.group = lookup_account_name(&group_db, metadata.gid());
+        access.mode = Some(metadata.mode() & 0o7777);
+
+        let c_path = match CString::new(access.path.as_os_str().as_bytes()) {
+            Ok(c_path) => c_path,
+            Err(e) => {
+                access.problems.push(AccessProblem::Other(e.to_string()));
+                return access;
+            }
+        };
+        let flags = libc::R_OK | libc::W_OK;
+        if unsafe { libc::faccessat(libc::AT_FDCWD, c_path.as_ptr(), flags, libc::AT_EACCESS) } == 0
+        {
+            access.can_open = true;
+            return access;
+        }
+        let error = io::Error::last_os_error();
+
+        let acl_name = b"system.posix_acl_access\0";
+        let has_acl = unsafe {
+            libc::getxattr(
+                c_path.as_ptr(),
+                acl_name.as_ptr().cast(),
+                std::ptr::null_mut(),
+                0,
+            )
+        } >= 0;
+        let credentials = Credentials::current(&passwd);
+        let group_members = lookup_group_members(&group_db, metadata.gid());
+        access.problems = find_access_problems(&access, &credentials, &group_members, has_acl);
+        if access.problems.is_empty() {
+            access
+                .problems
+                .push(AccessProblem::Other(error.to_string()));
+        }
+        return access;
+    }
+}
+
+/// the open file `fd` in procfs refers to the port
+fn is_port_fd(fd: &Path, node: &Path, devfs: &Path, device_number: Option<DeviceNumber>) -> bool {
+    let target = match fs::read_link(fd) {
+        Ok(target) => target,
+        Err(_) => return false,
+    };
+    if target == node {
+        return true;
+    }
+    // only stat device nodes, stat of a file on a hung network mount may block forever
+    let is_device_node = target.starts_with(devfs) || target.starts_with("/dev");
+    return is_device_node
+        && device_number.is_some()
+        && fs::metadata(fd)
+            .is_ok_and(|metadata| get_node_device_number(&metadata) == device_number);
+}
+
+/// real user id from the content of `/proc/<pid>/status`
+fn parse_status_uid(status: &str) -> Option<u32> {
+    return status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|uids| uids.split_whitespace().next())
+        .and_then(|uid| uid.parse().ok());
+}
+
+/// controlling tty from the content of `/proc/<pid>/stat`, `None` without one
+fn parse_stat_tty(stat: &str) -> Option<DeviceNumber> {
+    // the command in parentheses may contain spaces, tty_nr is the fifth field after it
+    let (_, fields) = stat.rsplit_once(')')?;
+    let tty_nr: u32 = fields.split_whitespace().nth(4)?.parse().ok()?;
+    if tty_nr == 0 {
+        return None;
+    }
+    return Some(DeviceNumber {
+        major: (tty_nr >> 8) & 0xfff,
+        minor: (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00),
+    });
+}
+
+/// pid of an UUCP lock file, in ASCII or in the old binary format
+fn parse_lock_pid(content: &[u8]) -> Option<u32> {
+    if let Some(pid) = std::str::from_utf8(content)
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+    {
+        return Some(pid);
+    }
+    return content.try_into().ok().map(u32::from_ne_bytes);
+}
+
+impl SerialInfo {
+    /// linux only, processes and UUCP lock files which keep the port busy
+    pub fn usage(&self) -> PortUsage {
+        return self.usage_from(&EnumerationRoot::default());
+    }
+
+    /// linux only, [`SerialInfo::usage`] with procfs, devfs, `/etc` and lock directories below `root`
+    pub fn usage_from(&self, root: &EnumerationRoot) -> PortUsage {
+        // services which open serial ports on their own
+        const KNOWN_GRABBERS: [&str; 4] = ["ModemManager", "brltty", "gpsd", "pppd"];
+        let node = match &self.device_node {
+            Some(device_node) => device_node.clone(),
+            None => PathBuf::from(&self.name),
+        };
+        let passwd = fs::read_to_string(root.etc.join("passwd")).unwrap_or_default();
+        let mut usage = PortUsage::default();
+
+        for entry in fs::read_dir(&root.procfs).into_iter().flatten().flatten() {
+            let pid: u32 = match entry.file_name().to_str().and_then(|pid| pid.parse().ok()) {
+                Some(pid) => pid,
+                None => continue,
+            };
+            let fds = match fs::read_dir(entry.path().join("fd")) {
+                Ok(fds) => fds,
+                Err(e) => {
+                    // the process may have exited in the meantime
+                    usage.incomplete |= e.kind() == io::ErrorKind::PermissionDenied;
+                    continue;
+                }
+            };
+            let holds_port = fds
+                .flatten()
+                .any(|fd| is_port_fd(&fd.path(), &node, &root.devfs, self.device_number));
+            if !holds_port {
+                continue;
+            }
+            let command = fs::read_to_string(entry.path().join("comm"))
+                .ok()
+                .map(|comm| comm.trim_end().to_string());
+            let uid = fs::read_to_string(entry.path().join("status"))
+                .ok()
+                .and_then(|status| parse_status_uid(&status));
+            let known_grabber = command.as_deref().is_some_and(|command| {
+                KNOWN_GRABBERS.contains(&command) || command.ends_with("getty")
+            });
+            usage.holders.push(PortHolder {
+                pid,
+                command,
+                uid,
+                user: uid.and_then(|uid| lookup_account_name(&passwd, uid)),
+                known_grabber,
+            });
+        }
+        usage.holders.sort_by_key(|holder| holder.pid);
+
+        if let Some(name) = Path::new(&self.name).file_name() {
+            let mut lock_name = std::ffi::OsString::from("LCK..");
+            lock_name.push(name);
+            let mut lock_files: Vec<PathBuf> = Vec::new();
+            for lock_dir in &root.lock_dirs {
+                let path = lock_dir.join(&lock_name);
+                // `/var/lock` is usually a link to `/run/lock`
+                let real_path = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                if lock_files.contains(&real_path) {
+                    continue;
+                }
+                if let Ok(content) = fs::read(&path) {
+                    let pid = parse_lock_pid(&content);
+                    usage.locks.push(PortLock {
+                        path,
+                        pid,
+                        alive: pid.is_some_and(|pid| root.procfs.join(pid.to_string()).exists()),
+                    });
+                    lock_files.push(real_path);
+                }
+            }
+        }
+        return usage;
+    }
+}
+
+impl SerialInfo {
+    /// linux only, getty process serving a login prompt on the port
+    pub fn getty(&self) -> Option<PortHolder> {
+        return self.getty_from(&EnumerationRoot::default());
+    }
+
+    /// linux only, [`SerialInfo::getty`] with procfs and `/etc` below `root`
+    pub fn getty_from(&self, root: &EnumerationRoot) -> Option<PortHolder> {
+        let name = Path::new(&self.name).file_name()?;
+        let passwd = fs::read_to_string(root.etc.join("passwd")).unwrap_or_default();
+        let mut gettys = Vec::new();
+        for entry in fs::read_dir(&root.procfs).into_iter().flatten().flatten() {
+            let pid: u32 = match entry.file_name().to_str().and_then(|pid| pid.parse().ok()) {
+                Some(pid) => pid,
+                None => continue,
+            };
+            let command = match fs::read_to_string(entry.path().join("comm")) {
+                Ok(comm) if comm.trim_end().ends_with("getty") => comm.trim_end().to_string(),
+                _ => continue,
+            };
+            // the controlling tty in the world-readable stat, systemd starts
+            // `agetty ... 115200,57600 - vt220` with the tty as stdin
+            let is_controlling_tty = self.device_number.is_some()
+                && fs::read_to_string(entry.path().join("stat"))
+                    .ok()
+                    .and_then(|stat| parse_stat_tty(&stat))
+                    == self.device_number;
+            if !is_controlling_tty {
+                // otherwise the tty is an argument, such as `agetty -o '-p -- \u' 115200 ttyS0 vt220`,
+                // the file descriptors are only readable as root
+                let mut ttys: Vec<PathBuf> = fs::read(entry.path().join("cmdline"))
+                    .unwrap_or_default()
+                    .split(|c| *c == 0)
+                    .map(|arg| PathBuf::from(String::from_utf8_lossy(arg).into_owned()))
+                    .collect();
+                for fd in fs::read_dir(entry.path().join("fd"))
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                {
+                    ttys.extend(fs::read_link(fd.path()));
+                }
+                if !ttys.iter().any(|tty| tty.file_name() == Some(name)) {
+                    continue;
+                }
+            }
+            let uid = fs::read_to_string(entry.path().join("status"))
+                .ok()
+                .and_then(|status| parse_status_uid(&status));
+            gettys.push(PortHolder {
+                pid,
+                command: Some(command),
+                uid,
+                user: uid.and_then(|uid| lookup_account_name(&passwd, uid)),
+                known_grabber: true,
+            });
+        }
+        return gettys.into_iter().min_by_key(|getty| getty.pid);
+    }
+}
+
+/// list tty drivers registered in the kernel, errors are ignored
+pub fn list_tty_drivers() -> Vec<TtyDriver> {
+    return try_list_tty_drivers().unwrap_or_default();
+}
+
+/// list tty drivers of the procfs below `root`, errors are ignored
+pub fn list_tty_drivers_from(root: &EnumerationRoot) -> Vec<TtyDriver> {
+    return try_list_tty_drivers_from(root).unwrap_or_default();
+}
+
+/// list tty drivers registered in the kernel from `/proc/tty/drivers`
+pub fn try_list_tty_drivers() -> Result<Vec<TtyDriver>, EnumerationError> {
+    return try_list_tty_drivers_from(&EnumerationRoot::default());
+}
+
+/// list tty drivers of the procfs below `root`
+pub fn try_list_tty_drivers_from(
+    root: &EnumerationRoot,
+) -> Result<Vec<TtyDriver>, EnumerationError> {
+    return read_tty_drivers(root);
+}
+
+/// enumerate all avaliable serial port, errors are ignored
 pub fn get_serial_list() -> Vec<SerialInfo> {
+    return try_get_serial_list().unwrap_or_default();
+}
+
+/// enumerate all avaliable serial port below the given sysfs, procfs and devfs roots,
+/// errors are ignored
+pub fn get_serial_list_from(root: &EnumerationRoot) -> Vec<SerialInfo> {
+    return try_get_serial_list_from(root).unwrap_or_default();
+}
+
+/// enumerate all avaliable serial port with options, errors are ignored
+pub fn get_serial_list_with(options: &EnumerationOptions) -> Vec<SerialInfo> {
+    return try_get_serial_list_with(options).unwrap_or_default();
+}
+
+/// enumerate all avaliable serial port
+///
+/// Errors which only affect a single port are reported in [`SerialInfo::errors`].
+pub fn try_get_serial_list() -> Result<Vec<SerialInfo>, EnumerationError> {
+    return try_get_serial_list_with(&EnumerationOptions::default());
+}
+
+/// enumerate all avaliable serial port below the given sysfs, procfs and devfs roots
+pub fn try_get_serial_list_from(
+    root: &EnumerationRoot,
+) -> Result<Vec<SerialInfo>, EnumerationError> {
+    return try_get_serial_list_with(&EnumerationOptions {
+        root: root.clone(),
+        ..Default::default()
+    });
+}
+
+/// enumerate all avaliable serial port with options
+pub fn try_get_serial_list_with(
+    options: &EnumerationOptions,
+) -> Result<Vec<SerialInfo>, EnumerationError> {
     let mut serial_list = Vec::new();
-    let serial_prefix = get_serial_prefix();
-    probe_serial_by_prefix(&mut serial_list, &serial_prefix);
-    return serial_list;
+    let serial_prefix = get_serial_prefix(&options.root)?;
+    probe_serial_by_prefix(options, &mut serial_list, &serial_prefix)?;
+    if options.include_virtual {
+        probe_virtual_serial(options, &mut serial_list, &serial_prefix);
+    }
+    resolve_consoles(&options.root, &mut serial_list);
+    resolve_uart_stats(&options.root, &mut serial_list);
+    if !serial_list.is_empty() {
+        let devfs_entries = scan_devfs(&options.root);
+        resolve_device_nodes(&devfs_entries.nodes, &mut serial_list);
+        resolve_links(options, devfs_entries.links, &mut serial_list);
+    }
+    resolve_device_tree_aliases(&options.root, &mut serial_list);
+    return Ok(serial_list);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usb_descriptors::ARDUINO_UNO;
+    use crate::UsbClassDescriptor;
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    /// sysfs, procfs and devfs tree in a temporary directory
+    pub struct Fixture {
+        base: PathBuf,
+        pub root: EnumerationRoot,
+    }
+
+    impl Fixture {
+        pub fn new(name: &str) -> Fixture {
+            let base = std::env::temp_dir().join(format!(
+                "serial_enumerator-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&base);
+            fs::create_dir_all(&base).unwrap();
+            let root = EnumerationRoot::with_prefix(&base);
+            return Fixture { base, root };
+        }
+
+        /// write `content` to `path`, parent directories are created
+        pub fn write<C: AsRef<[u8]>>(&self, path: &str, content: C) -> &Self {
+            let path = self.base.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+            return self;
+        }
+
+        /// create a symlink at `link` that points to `target`, a missing target is created
+        /// as directory
+        pub fn link(&self, link: &str, target: &str) -> &Self {
+            let link = self.base.join(link);
+            let target = self.base.join(target);
+            fs::create_dir_all(link.parent().unwrap()).unwrap();
+            if !target.exists() {
+                fs::create_dir_all(&target).unwrap();
+            }
+            symlink(target, link).unwrap();
+            return self;
+        }
+
+        /// register tty `name` in `sys/class/tty` for the device at `device`
+        pub fn tty(&self, name: &str, device: &str, driver: &str) -> &Self {
+            let tty_path = format!("{}/tty/{}", device, name);
+            self.link(&format!("sys/class/tty/{}", name), &tty_path);
+            self.link(&format!("{}/device", tty_path), device);
+            self.link(&format!("{}/driver", device), driver);
+            return self;
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.base);
+        }
+    }
+
+    const FT2232_DEVICE: &str = "sys/devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2.3";
+
+    pub fn ft2232_fixture(name: &str) -> Fixture {
+        let fixture = Fixture::new(name);
+        let interface = format!("{}/1-2.3:1.0", FT2232_DEVICE);
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "usbserial            /dev/ttyUSB   188 0-511 serial\n",
+            )
+            .write(&format!("{}/manufacturer", FT2232_DEVICE), "FTDI\n")
+            .write(&format!("{}/product", FT2232_DEVICE), "Dual RS232-HS\n")
+            .write(&format!("{}/idVendor", FT2232_DEVICE), "0403\n")
+            .write(&format!("{}/idProduct", FT2232_DEVICE), "6010\n")
+            .write(&format!("{}/serial", FT2232_DEVICE), "FT4ZK3QA\n")
+            .write(&format!("{}/bcdDevice", FT2232_DEVICE), "0700\n")
+            .write(&format!("{}/busnum", FT2232_DEVICE), "1\n")
+            .write(&format!("{}/devnum", FT2232_DEVICE), "12\n")
+            .write(&format!("{}/bInterfaceNumber", interface), "00\n")
+            .write(&format!("{}/interface", interface), "Dual RS232-HS\n")
+            .tty(
+                "ttyUSB0",
+                &format!("{}/ttyUSB0", interface),
+                "sys/bus/usb-serial/drivers/ftdi_sio",
+            );
+        return fixture;
+    }
+
+    #[test]
+    fn test_resolve_links() {
+        let fixture = ft2232_fixture("links");
+        fixture
+            .write("dev/ttyUSB0", "")
+            .write("dev/ttyS0", "")
+            .link(
+                "dev/serial/by-id/usb-FTDI_Dual_RS232-HS_FT4ZK3QA-if00-port0",
+                "dev/ttyUSB0",
+            )
+            .link(
+                "dev/serial/by-path/pci-0000:00:14.0-usb-0:2.3:1.0-port0",
+                "dev/ttyUSB0",
+            )
+            .link("dev/gps", "dev/ttyUSB0")
+            .link("dev/console0", "dev/ttyS0")
+            .link("proc/self/fd/0", "dev/ttyUSB0")
+            .link("dev/stdin", "proc/self/fd/0");
+        let serial_list = get_serial_list_from(&fixture.root);
+        let links: Vec<_> = serial_list[0]
+            .links
+            .iter()
+            .map(|link| link.strip_prefix(&fixture.root.devfs).unwrap())
+            .collect();
+        assert_eq!(
+            links,
+            [
+                Path::new("gps"),
+                Path::new("serial/by-id/usb-FTDI_Dual_RS232-HS_FT4ZK3QA-if00-port0"),
+                Path::new("serial/by-path/pci-0000:00:14.0-usb-0:2.3:1.0-port0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_probe_usb_serial() {
+        let fixture = ft2232_fixture("usb");
+        let hub_port = "sys/devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/1-2-port3";
+        fixture
+            .write(&format!("{}/speed", FT2232_DEVICE), "480\n")
+            .write(&format!("{}/version", FT2232_DEVICE), " 2.00\n")
+            .write(&format!("{}/bMaxPower", FT2232_DEVICE), "100mA\n")
+            .write(&format!("{}/bmAttributes", FT2232_DEVICE), "80\n")
+            .write(&format!("{}/removable", FT2232_DEVICE), "fixed\n")
+            .write(&format!("{}/authorized", FT2232_DEVICE), "1\n")
+            .write(&format!("{}/connect_type", hub_port), "hardwired\n")
+            .link(&format!("{}/port", FT2232_DEVICE), hub_port)
+            .write(
+                "sys/module/ftdi_sio/srcversion",
+                "5B2C5F9A7A4C3D2E1F0A9B8\n",
+            )
+            .link(
+                "sys/bus/usb-serial/drivers/ftdi_sio/module",
+                "sys/module/ftdi_sio",
+            )
+            .link("sys/bus/usb/drivers/ftdi_sio/module", "sys/module/ftdi_sio")
+            .link(
+                &format!("{}/1-2.3:1.0/driver", FT2232_DEVICE),
+                "sys/bus/usb/drivers/ftdi_sio",
+            );
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(serial_list.len(), 1);
+        let serial_info = &serial_list[0];
+        assert_eq!(
+            serial_info.name,
+            fixture.root.devfs.join("ttyUSB0").to_string_lossy()
+        );
+        assert_eq!(serial_info.vendor.as_deref(), Some("FTDI"));
+        assert_eq!(serial_info.product.as_deref(), Some("Dual RS232-HS"));
+        assert_eq!(serial_info.driver.as_deref(), Some("ftdi_sio"));
+        let usb_info = serial_info.usb_info.as_ref().unwrap();
+        assert_eq!(
+            (usb_info.vid.as_str(), usb_info.pid.as_str()),
+            ("0403", "6010")
+        );
+        assert_eq!(serial_info.kind, PortKind::Usb);
+        assert_eq!(usb_info.serial_number.as_deref(), Some("FT4ZK3QA"));
+        assert_eq!(usb_info.bcd_device.as_deref(), Some("0700"));
+        assert_eq!(usb_info.interface_number, Some(0));
+        assert_eq!(usb_info.interface.as_deref(), Some("Dual RS232-HS"));
+        assert_eq!(
+            usb_info.location,
+            Some(UsbLocation {
+                busnum: 1,
+                devnum: 12,
+                port_path: "1-2.3".into(),
+                interface: Some("1.0".into()),
+            })
+        );
+        let link = usb_info.link.as_ref().unwrap();
+        assert_eq!(
+            *link,
+            UsbLinkInfo {
+                speed: Some("480".into()),
+                version: Some("2.00".into()),
+                max_power: Some(100),
+                attributes: Some(0x80),
+                removable: Some(false),
+                connect_type: Some(UsbConnectType::Hardwired),
+                authorized: Some(true),
+            }
+        );
+        assert_eq!(link.self_powered(), Some(false));
+
+        let driver_info = serial_info.driver_info.as_ref().unwrap();
+        assert_eq!(
+            *driver_info,
+            DriverInfo {
+                name: "ftdi_sio".into(),
+                module: Some("ftdi_sio".into()),
+                version: None,
+                srcversion: Some("5B2C5F9A7A4C3D2E1F0A9B8".into()),
+                bus: Some("usb-serial".into()),
+            }
+        );
+        let interface_driver = serial_info.interface_driver.as_ref().unwrap();
+        assert_eq!(interface_driver.name, "ftdi_sio");
+        assert_eq!(interface_driver.bus.as_deref(), Some("usb"));
+    }
+
+    #[cfg(feature = "usb-ids")]
+    #[test]
+    fn test_fill_names_from_usb_ids() {
+        let fixture = Fixture::new("usb-ids");
+        let device = "sys/devices/pci0000:00/0000:00:14.0/usb1/1-4";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "usbserial            /dev/ttyUSB   188 0-511 serial\n",
+            )
+            .write(&format!("{}/product", device), "USB2.0-Serial\n")
+            .write(&format!("{}/idVendor", device), "1a86\n")
+            .write(&format!("{}/idProduct", device), "7523\n")
+            .tty(
+                "ttyUSB0",
+                &format!("{}/1-4:1.0/ttyUSB0", device),
+                "sys/bus/usb-serial/drivers/ch341-uart",
+            );
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(
+            serial_list[0].vendor.as_deref(),
+            Some("QinHeng Electronics")
+        );
+        assert!(serial_list[0].vendor_from_database);
+        assert_eq!(serial_list[0].product.as_deref(), Some("USB2.0-Serial"));
+        assert!(!serial_list[0].product_from_database);
+    }
+
+    #[test]
+    fn test_read_udev_info() {
+        let fixture = ft2232_fixture("udev");
+        fixture.write("sys/class/tty/ttyUSB0/dev", "188:0\n");
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert!(serial_list[0].udev.is_none());
+        assert!(serial_list[0].errors.is_empty());
+
+        fixture.write(
+            "run/udev/data/c188:0",
+            "I:5170419088\nE:ID_SERIAL=FTDI_Dual_RS232-HS_FT4ZK3QA\n",
+        );
+        let serial_list = get_serial_list_from(&fixture.root);
+        let udev_info = serial_list[0].udev.as_ref().unwrap();
+        assert_eq!(udev_info.serial(), Some("FTDI_Dual_RS232-HS_FT4ZK3QA"));
+        assert_eq!(udev_info.initialized_usec, Some(5170419088));
+    }
+
+    #[test]
+    fn test_resolve_device_nodes() {
+        let fixture = ft2232_fixture("nodes");
+        fixture.write("sys/class/tty/ttyUSB0/dev", "188:0\n");
+        let serial_list = get_serial_list_from(&fixture.root);
+        let serial_info = &serial_list[0];
+        assert_eq!(
+            serial_info.device_number,
+            Some(DeviceNumber {
+                major: 188,
+                minor: 0
+            })
+        );
+        assert_eq!(serial_info.device_node, None);
+        assert_eq!(
+            Path::new(&serial_info.name),
+            fixture.root.devfs.join("ttyUSB0")
+        );
+        assert!(serial_info.errors.is_empty());
+
+        // a node renamed by udev is found by its device number, here /dev/null
+        fixture.write("sys/class/tty/ttyUSB0/dev", "1:3\n");
+        let root = EnumerationRoot {
+            devfs: PathBuf::from("/dev"),
+            ..fixture.root.clone()
+        };
+        let serial_list = get_serial_list_from(&root);
+        assert_eq!(serial_list[0].name, "/dev/null");
+        assert_eq!(serial_list[0].device_node, Some(PathBuf::from("/dev/null")));
+
+        fixture.write("sys/class/tty/ttyUSB0/dev", "188\n");
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(serial_list[0].device_number, None);
+        assert!(matches!(
+            serial_list[0].errors[..],
+            [EnumerationError::Io(_, _)]
+        ));
+    }
+
+    #[test]
+    fn test_probe_acm_serial() {
+        let fixture = Fixture::new("acm");
+        let device = "sys/devices/pci0000:00/0000:00:14.0/usb1/1-3";
+        let interface = format!("{}/1-3:1.0", device);
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "acm                  /dev/ttyACM   166 0-255 serial\n",
+            )
+            .write(
+                &format!("{}/manufacturer", device),
+                "Arduino (www.arduino.cc)\n",
+            )
+            .write(&format!("{}/idVendor", device), "2341\n")
+            .write(&format!("{}/idProduct", device), "0043\n")
+            .write(&format!("{}/bConfigurationValue", device), "1\n")
+            .write(&format!("{}/descriptors", device), ARDUINO_UNO)
+            .write(&format!("{}/bInterfaceNumber", interface), "00\n")
+            .write(&format!("{}/bAlternateSetting", interface), " 0\n")
+            .link(&format!("{}/subsystem", interface), "sys/bus/usb")
+            .tty("ttyACM0", &interface, "sys/bus/usb/drivers/cdc_acm");
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(serial_list.len(), 1);
+        let serial_info = &serial_list[0];
+        assert_eq!(
+            serial_info.vendor.as_deref(),
+            Some("Arduino (www.arduino.cc)")
+        );
+        assert_eq!(serial_info.driver.as_deref(), Some("cdc_acm"));
+        let usb_info = serial_info.usb_info.as_ref().unwrap();
+        assert_eq!(usb_info.pid, "0043");
+        assert_eq!(
+            usb_info.descriptors.as_ref().unwrap().device.vendor_id,
+            0x2341
+        );
+        let interface_descriptor = usb_info.interface_descriptor.as_ref().unwrap();
+        assert_eq!(interface_descriptor.number, 0);
+        assert_eq!(interface_descriptor.endpoints[0].address, 0x82);
+        let acm_info = serial_info.acm_info.as_ref().unwrap();
+        assert_eq!(acm_info.capabilities, 0x06);
+        assert!(acm_info.supports_line_coding() && acm_info.supports_break());
+        assert_eq!(
+            (acm_info.control_interface, acm_info.data_interface),
+            (Some(0), Some(1))
+        );
+
+        // firmware without SET_LINE_CODING, reported by the driver in sysfs
+        fixture.write(&format!("{}/bmCapabilities", interface), "0\n");
+        let serial_list = get_serial_list_from(&fixture.root);
+        let acm_info = serial_list[0].acm_info.as_ref().unwrap();
+        assert!(!acm_info.supports_line_coding());
+
+        fixture.write(&format!("{}/descriptors", device), &ARDUINO_UNO[..20]);
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert!(serial_list[0]
+            .usb_info
+            .as_ref()
+            .unwrap()
+            .descriptors
+            .is_none());
+        assert!(matches!(
+            serial_list[0].errors[..],
+            [EnumerationError::MalformedDescriptors(_, _)]
+        ));
+    }
+
+    #[test]
+    fn test_decode_acm_descriptors() {
+        let interface_descriptor = UsbInterfaceDescriptor {
+            extra: vec![
+                UsbClassDescriptor {
+                    descriptor_type: 0x24,
+                    data: vec![0x01, 0x03, 0x02],
+                },
+                UsbClassDescriptor {
+                    descriptor_type: 0x24,
+                    data: vec![0x07, 0x04, 0x44, 0x45, 0x55, 0x53],
+                },
+            ],
+            ..Default::default()
+        };
+        let mut acm_info = AcmInfo::default();
+        decode_acm_descriptors(&interface_descriptor, &mut acm_info);
+        assert_eq!(acm_info.data_interface, Some(2));
+        assert_eq!(acm_info.country_code_release_index, Some(4));
+        assert_eq!(acm_info.country_codes, [0x4544, 0x5355]);
+    }
+
+    #[test]
+    fn test_probe_builtin_serial() {
+        let fixture = Fixture::new("builtin");
+        let pnp = "sys/devices/pnp0/00:04";
+        let platform = "sys/devices/platform/soc/fe201000.serial";
+        let of_node = "sys/firmware/devicetree/base/soc/serial@7e201000";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "serial               /dev/ttyS       4 64-111 serial\n\
+                 ttyAMA               /dev/ttyAMA   204 64-77 serial\n",
+            )
+            .write(&format!("{}/id", pnp), "PNP0501\n")
+            .link(&format!("{}/subsystem", pnp), "sys/bus/pnp")
+            .tty("ttyS0", pnp, "sys/bus/pnp/drivers/serial")
+            .write(
+                &format!("{}/compatible", of_node),
+                "arm,pl011\0arm,primecell\0",
+            )
+            .write(&format!("{}/status", of_node), "okay\0")
+            .write(
+                &format!("{}/current-speed", of_node),
+                115200u32.to_be_bytes(),
+            )
+            .write(
+                "sys/firmware/devicetree/base/aliases/serial0",
+                "/soc/serial@7e201000\0",
+            )
+            .write(
+                "sys/firmware/devicetree/base/aliases/uart0",
+                "/soc/serial@7e201000\0",
+            )
+            .write(
+                "sys/firmware/devicetree/base/aliases/serial1",
+                "/soc/serial@7e215040\0",
+            )
+            .link("proc/device-tree", "sys/firmware/devicetree/base")
+            .link(&format!("{}/of_node", platform), of_node)
+            .link(&format!("{}/subsystem", platform), "sys/bus/amba")
+            .tty("ttyAMA0", platform, "sys/bus/amba/drivers/uart-pl011");
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(serial_list.len(), 2);
+        assert_eq!(serial_list[1].vendor.as_deref(), Some("pnp"));
+        assert_eq!(serial_list[1].product.as_deref(), Some("PNP0501"));
+        assert_eq!(serial_list[0].vendor.as_deref(), Some("amba"));
+        assert_eq!(
+            serial_list[0].product.as_deref(),
+            Some("arm,pl011;arm,primecell;")
+        );
+        assert_eq!(
+            serial_list[0].device_tree,
+            Some(DeviceTreeInfo {
+                path: "/soc/serial@7e201000".into(),
+                compatible: vec!["arm,pl011".into(), "arm,primecell".into()],
+                status: Some("okay".into()),
+                current_speed: Some(115200),
+                aliases: vec!["serial0".into(), "uart0".into()],
+            })
+        );
+        assert_eq!(serial_list[1].device_tree, None);
+        assert_eq!(serial_list[0].rs485, None);
+    }
+
+    #[test]
+    fn test_uart_type_name() {
+        let fixture = Fixture::new("uart_type");
+        fixture.write(
+            "proc/tty/drivers",
+            "xuartps              /dev/ttyPS    251 0-1 serial\n\
+             IMX-uart             /dev/ttymxc   207 16-23 serial\n",
+        );
+        for (tty, unit, compatible, driver, uart_type) in [
+            (
+                "ttyPS0",
+                "ff000000.serial",
+                "xlnx,zynqmp-uart\0",
+                "xuartps",
+                "98",
+            ),
+            (
+                "ttymxc0",
+                "2020000.serial",
+                "fsl,imx6q-uart\0",
+                "imx-uart",
+                "62",
+            ),
+        ] {
+            let platform = format!("sys/devices/platform/soc/{}", unit);
+            let of_node = format!("sys/firmware/devicetree/base/soc/serial@{}", unit);
+            fixture
+                .write(&format!("{}/compatible", of_node), compatible)
+                .link(&format!("{}/of_node", platform), &of_node)
+                .tty(
+                    tty,
+                    &platform,
+                    &format!("sys/bus/platform/drivers/{}", driver),
+                )
+                .write(&format!("sys/class/tty/{}/type", tty), uart_type)
+                .write(&format!("sys/class/tty/{}/iomem_base", tty), "0xFF000000\n");
+        }
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        let type_names: Vec<_> = serial_list
+            .iter()
+            .map(|serial_info| serial_info.uart_info.as_ref().unwrap().type_name())
+            .collect();
+        assert_eq!(type_names, [Some("XUARTPS"), Some("IMX")]);
+        let uart_info = serial_list[0].uart_info.as_ref().unwrap();
+        assert_eq!(
+            uart_info.platform_device.as_deref(),
+            Some("ff000000.serial")
+        );
+        assert_eq!(
+            UartInfo {
+                uart_type: 43,
+                ..uart_info.clone()
+            }
+            .type_name(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_read_rs485_config() {
+        let fixture = Fixture::new("rs485");
+        let platform = "sys/devices/platform/soc/2020000.serial";
+        let of_node = "sys/firmware/devicetree/base/soc/serial@2020000";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "IMX-uart             /dev/ttymxc   207 16-23 serial\n",
+            )
+            .write(&format!("{}/compatible", of_node), "fsl,imx6q-uart\0")
+            .write(&format!("{}/rts-gpios", of_node), [0; 12])
+            .link(&format!("{}/of_node", platform), of_node)
+            .tty("ttymxc0", platform, "sys/bus/platform/drivers/imx-uart");
+        // RS-232 port with RTS on a gpio
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(serial_list[0].rs485, None);
+
+        fixture.write(
+            &format!("{}/rs485-rts-delay", of_node),
+            [0, 0, 0, 1, 0, 0, 0, 2],
+        );
+        let serial_list = get_serial_list_from(&fixture.root);
+        let rs485 = serial_list[0].rs485.as_ref().unwrap();
+        assert_eq!(rs485.rts_delay_before_send, Some(1));
+        assert_eq!(rs485.rts_delay_after_send, Some(2));
+        assert!(rs485.rts_gpios);
+        assert!(rs485.is_half_configured());
+
+        fixture.write(&format!("{}/linux,rs485-enabled-at-boot-time", of_node), "");
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert!(!serial_list[0].rs485.as_ref().unwrap().is_half_configured());
+    }
+
+    #[test]
+    fn test_phantom_ports() {
+        let fixture = Fixture::new("phantom");
+        let serial8250 = "sys/devices/platform/serial8250";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "serial               /dev/ttyS       4 64-111 serial\n\
+                 sc16is7xx            /dev/ttySC    240 0-7 serial\n",
+            )
+            .write("sys/devices/pnp0/00:04/id", "PNP0501\n")
+            .tty(
+                "ttyS0",
+                "sys/devices/pnp0/00:04",
+                "sys/bus/pnp/drivers/serial",
+            )
+            .write("sys/class/tty/ttyS0/type", "4\n")
+            .write("sys/class/tty/ttyS0/port", "0x3F8\n")
+            .write("sys/class/tty/ttyS0/iomem_base", "0x0\n")
+            .write("sys/class/tty/ttyS0/irq", "4\n")
+            .write("sys/class/tty/ttyS0/uartclk", "1843200\n")
+            .write("sys/class/tty/ttyS0/xmit_fifo_size", "16\n")
+            .write("sys/class/tty/ttyS0/io_type", "0\n")
+            .write("sys/class/tty/ttyS0/close_delay", "50\n")
+            .write("sys/class/tty/ttyS0/flags", "0x10000040\n")
+            .link(&format!("{}/subsystem", serial8250), "sys/bus/platform")
+            .tty("ttyS1", serial8250, "sys/bus/platform/drivers/serial8250")
+            .write("sys/class/tty/ttyS1/type", "0\n")
+            .write("sys/class/tty/ttyS1/port", "0x2F8\n")
+            .write("sys/class/tty/ttyS1/iomem_base", "0x0\n")
+            .write(
+                "sys/firmware/devicetree/base/spi/serial@0/compatible",
+                "nxp,sc16is752\0",
+            )
+            .link(
+                "sys/devices/platform/spi/spi0.0/of_node",
+                "sys/firmware/devicetree/base/spi/serial@0",
+            )
+            .tty(
+                "ttySC0",
+                "sys/devices/platform/spi/spi0.0",
+                "sys/bus/spi/drivers/sc16is7xx",
+            )
+            .write("sys/class/tty/ttySC0/type", "19\n")
+            .write("sys/class/tty/ttySC0/port", "0x0\n")
+            .write("sys/class/tty/ttySC0/iomem_base", "0x0\n");
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(serial_list.len(), 2);
+        assert!(serial_list[0].name.ends_with("ttyS0"));
+        // bridge on SPI with `port` and `iomem_base` 0
+        assert!(serial_list[1].name.ends_with("ttySC0"));
+        assert_eq!(serial_list[1].kind, PortKind::Builtin);
+        let uart_info = serial_list[0].uart_info.as_ref().unwrap();
+        assert_eq!(uart_info.type_name(), Some("16550A"));
+        assert_eq!(uart_info.port, Some(0x3f8));
+        assert_eq!(uart_info.iomem_base, Some(0));
+        assert_eq!(uart_info.irq, Some(4));
+        assert_eq!(uart_info.uartclk, Some(1843200));
+        assert_eq!(uart_info.xmit_fifo_size, Some(16));
+        assert_eq!(uart_info.close_delay, Some(50));
+        assert_eq!(uart_info.flags, Some(0x10000040));
+        assert_eq!(uart_info.platform_device.as_deref(), Some("00:04"));
+
+        let mut serial_list = get_serial_list_with(&EnumerationOptions {
+            root: fixture.root.clone(),
+            include_phantom_ports: true,
+            ..Default::default()
+        });
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(serial_list.len(), 3);
+        assert_eq!(serial_list[1].vendor.as_deref(), Some("platform"));
+        assert_eq!(serial_list[1].product.as_deref(), Some("serial8250"));
+    }
+
+    #[test]
+    fn test_probe_virtual_serial() {
+        let fixture = Fixture::new("virtual");
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "tnt                  /dev/tnt      240 0-7 serial\n",
+            )
+            .link("sys/class/tty/tnt0", "sys/devices/virtual/tty/tnt0")
+            .link("sys/class/tty/tnt1", "sys/devices/virtual/tty/tnt1")
+            .link(
+                "sys/class/tty/ttyprintk",
+                "sys/devices/virtual/tty/ttyprintk",
+            )
+            .link("sys/class/tty/tty1", "sys/devices/virtual/tty/tty1")
+            .write("dev/pts/ptmx", "")
+            .write("dev/pts/3", "")
+            .link("tmp/ttyV0", "dev/pts/3");
+        assert!(get_serial_list_from(&fixture.root).is_empty());
+
+        let mut serial_list = get_serial_list_with(&EnumerationOptions {
+            root: fixture.root.clone(),
+            include_virtual: true,
+            virtual_link_dirs: vec![fixture.base.join("tmp")],
+            ..Default::default()
+        });
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<_> = serial_list
+            .iter()
+            .map(|serial_info| {
+                Path::new(&serial_info.name)
+                    .strip_prefix(&fixture.root.devfs)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                Path::new("pts/3"),
+                Path::new("tnt0"),
+                Path::new("tnt1"),
+                Path::new("ttyprintk")
+            ]
+        );
+        assert!(serial_list
+            .iter()
+            .all(|serial_info| serial_info.kind == PortKind::Virtual));
+        assert_eq!(serial_list[0].links, [fixture.base.join("tmp/ttyV0")]);
+    }
+
+    #[test]
+    fn test_probe_gadget_serial() {
+        let fixture = Fixture::new("gadget");
+        let gadget = "sys/kernel/config/usb_gadget/g1";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "g_serial             /dev/ttyGS    237 0-3 serial\n",
+            )
+            .link("sys/class/tty/ttyGS0", "sys/devices/virtual/tty/ttyGS0")
+            .link("sys/class/tty/ttyGS1", "sys/devices/virtual/tty/ttyGS1")
+            .write(&format!("{}/UDC", gadget), "fe980000.usb\n")
+            .write(&format!("{}/strings/0x409/manufacturer", gadget), "Acme\n")
+            .write(
+                &format!("{}/strings/0x409/product", gadget),
+                "Debug Console\n",
+            )
+            .write(&format!("{}/functions/acm.usb0/port_num", gadget), "0\n")
+            .write(&format!("{}/functions/ecm.usb0/ifname", gadget), "usb0\n")
+            .link(
+                &format!("{}/configs/c.1/acm.usb0", gadget),
+                &format!("{}/functions/acm.usb0", gadget),
+            )
+            .write("sys/class/udc/fe980000.usb/state", "configured\n");
+
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(serial_list.len(), 2);
+
+        let serial_info = &serial_list[0];
+        assert_eq!(serial_info.kind, PortKind::Gadget);
+        assert_eq!(serial_info.vendor.as_deref(), Some("Acme"));
+        assert_eq!(serial_info.product.as_deref(), Some("Debug Console"));
+        assert_eq!(
+            serial_info.gadget_info,
+            Some(GadgetInfo {
+                gadget: Some("g1".into()),
+                function: Some("acm.usb0".into()),
+                udc: Some("fe980000.usb".into()),
+                configured: true,
+            })
+        );
+
+        // no function instance, for example a port of the legacy g_serial module
+        let serial_info = &serial_list[1];
+        assert_eq!(serial_info.kind, PortKind::Gadget);
+        assert_eq!(serial_info.gadget_info, Some(GadgetInfo::default()));
+        assert!(serial_info.errors.is_empty());
+    }
+
+    #[test]
+    fn test_access() {
+        let fixture = Fixture::new("access");
+        fixture.write("dev/ttyUSB0", "");
+        let mut serial_info = SerialInfo {
+            name: fixture
+                .root
+                .devfs
+                .join("ttyUSB1")
+                .to_string_lossy()
+                .into_owned(),
+            ..Default::default()
+        };
+        let access = serial_info.access();
+        assert!(!access.can_open);
+        assert_eq!(access.problems, [AccessProblem::NodeMissing]);
+
+        let node = fixture.root.devfs.join("ttyUSB0");
+        fs::set_permissions(&node, fs::Permissions::from_mode(0o600)).unwrap();
+        serial_info.device_node = Some(node.clone());
+        let uid = unsafe { libc::geteuid() };
+        let gid = fs::metadata(&node).unwrap().gid();
+        fixture
+            .write(
+                "etc/passwd",
+                format!("builder:x:{}:{}::/:/bin/sh\n", uid, gid),
+            )
+            .write("etc/group", format!("builders:x:{}:\n", gid));
+        let access = serial_info.access_from(&fixture.root);
+        assert_eq!(access.path, node);
+        assert_eq!(access.mode, Some(0o600));
+        assert_eq!(access.uid, Some(uid));
+        assert_eq!(access.owner.as_deref(), Some("builder"));
+        assert_eq!(access.group.as_deref(), Some("builders"));
+        assert!(access.can_open);
+        assert!(access.problems.is_empty());
+    }
+
+    #[test]
+    fn test_find_access_problems() {
+        let group_db = "root:x:0:\ndialout:x:20:alice,bob\nuucp:x:10:\n";
+        let access = PortAccess {
+            uid: Some(0),
+            gid: Some(20),
+            group: lookup_account_name(group_db, 20),
+            mode: Some(0o660),
+            ..Default::default()
+        };
+        let mut credentials = Credentials {
+            uid: 1000,
+            user: Some("carol".into()),
+            gids: vec![1000],
+        };
+        let members = lookup_group_members(group_db, 20);
+        assert_eq!(members, ["alice", "bob"]);
+        assert_eq!(
+            find_access_problems(&access, &credentials, &members, false),
+            [AccessProblem::NotInGroup("dialout".into())]
+        );
+
+        credentials.user = Some("alice".into());
+        assert_eq!(
+            find_access_problems(&access, &credentials, &members, true),
+            [
+                AccessProblem::AclMissing,
+                AccessProblem::GroupNotActive("dialout".into())
+            ]
+        );
+
+        credentials.gids.push(20);
+        let access = PortAccess {
+            mode: Some(0o600),
+            ..access
+        };
+        assert_eq!(
+            find_access_problems(&access, &credentials, &members, false),
+            [AccessProblem::ModeDenied]
+        );
+    }
+
+    #[test]
+    fn test_usage() {
+        let fixture = Fixture::new("usage");
+        fixture
+            .write("dev/ttyUSB0", "")
+            .write("dev/ttyUSB1", "")
+            .write("proc/512/comm", "ModemManager\n")
+            .write("proc/512/status", "Name:\tModemManager\nUid:\t0\t0\t0\t0\n")
+            .link("proc/512/fd/0", "dev/null")
+            .link("proc/512/fd/9", "dev/ttyUSB0")
+            .write("proc/2048/comm", "picocom\n")
+            .link("proc/2048/fd/3", "dev/ttyUSB1")
+            .write("proc/uptime", "")
+            .write("run/lock/LCK..ttyUSB0", "      4096\n")
+            .link("var/lock", "run/lock");
+        let serial_info = SerialInfo {
+            name: fixture
+                .root
+                .devfs
+                .join("ttyUSB0")
+                .to_string_lossy()
+                .into_owned(),
+            ..Default::default()
+        };
+        let usage = serial_info.usage_from(&fixture.root);
+        assert_eq!(usage.holders.len(), 1);
+        let holder = &usage.holders[0];
+        assert_eq!(holder.pid, 512);
+        assert_eq!(holder.command.as_deref(), Some("ModemManager"));
+        assert_eq!(holder.uid, Some(0));
+        assert!(holder.known_grabber);
+        assert_eq!(
+            usage.locks,
+            [PortLock {
+                path: fixture.root.run.join("lock/LCK..ttyUSB0"),
+                pid: Some(4096),
+                alive: false,
+            }]
+        );
+
+        fixture
+            .write("run/lock/LCK..ttyUSB0", 2048u32.to_ne_bytes())
+            .write("var/spool/uucp/LCK..ttyUSB0", "garbage\n");
+        let usage = serial_info.usage_from(&fixture.root);
+        assert_eq!(usage.locks.len(), 2);
+        assert!(usage.locks[0].alive);
+        assert_eq!(usage.locks[1].pid, None);
+    }
+
+    #[test]
+    fn test_resolve_consoles() {
+        let fixture = Fixture::new("consoles");
+        let device = "sys/devices/platform/serial8250/serial8250:0";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "serial               /dev/ttyS       4 64-111 serial\n",
+            )
+            .write(
+                "proc/consoles",
+                "ttyS0                -W- (EC p a)    4:64\n",
+            )
+            .write(
+                "proc/cmdline",
+                "BOOT_IMAGE=/vmlinuz console=tty0 console=ttyS0,115200n8 quiet\n",
+            )
+            .write("sys/class/tty/console/active", "tty0 ttyS0\n")
+            .write("proc/1/comm", "systemd\n")
+            // serial-getty@ttyS0 of systemd, the tty is only the controlling tty
+            .write("proc/580/comm", "agetty\n")
+            .write(
+                "proc/580/cmdline",
+                "/sbin/agetty\0-o\0-p -- \\u\0--keep-baud\x00115200,57600\0-\0vt220\0",
+            )
+            .write(
+                "proc/580/stat",
+                "580 (agetty) S 1 580 580 1088 580 4194560 120 0 0 0 0 0 0 0 20 0 1 0 812\n",
+            )
+            .write("proc/612/comm", "agetty\n")
+            .write("proc/612/status", "Name:\tagetty\nUid:\t0\t0\t0\t0\n")
+            .write("etc/passwd", "root:x:0:0:root:/root:/bin/bash\n")
+            .write(
+                "proc/612/cmdline",
+                "/sbin/agetty\0-o\0-p -- \\u\0--keep-baud\x00115200,57600\0ttyS1\0vt220\0",
+            );
+        for tty in ["ttyS0", "ttyS1"] {
+            let tty_path = format!("{}/tty/{}", device, tty);
+            fixture
+                .link(&format!("sys/class/tty/{}", tty), &tty_path)
+                .link(&format!("{}/device", tty_path), device)
+                .write(&format!("{}/type", tty_path), "4\n")
+                .write(&format!("{}/port", tty_path), "0x3F8\n");
+        }
+        fixture
+            .write(&format!("{}/tty/ttyS0/dev", device), "4:64\n")
+            .write(&format!("{}/tty/ttyS1/dev", device), "4:65\n");
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(serial_list.len(), 2);
+        assert_eq!(
+            serial_list[0].console,
+            Some(ConsoleInfo {
+                active: true,
+                preferred: true,
+                options: Some("115200n8".into()),
+            })
+        );
+        assert_eq!(serial_list[1].console, None);
+
+        let getty = serial_list[0].getty_from(&fixture.root).unwrap();
+        assert_eq!(getty.pid, 580);
+        let getty = serial_list[1].getty_from(&fixture.root).unwrap();
+        assert_eq!(getty.pid, 612);
+        assert_eq!(getty.command.as_deref(), Some("agetty"));
+        assert_eq!(getty.user.as_deref(), Some("root"));
+    }
+
+    #[test]
+    fn test_resolve_uart_stats() {
+        let fixture = Fixture::new("uart_stats");
+        let device = "sys/devices/platform/serial8250/serial8250:0";
+        let tty_path = format!("{}/tty/ttyS0", device);
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "serial               /dev/ttyS       4 64-111 serial\n",
+            )
+            .link("sys/class/tty/ttyS0", &tty_path)
+            .link(&format!("{}/device", tty_path), device)
+            .write(&format!("{}/type", tty_path), "4\n")
+            .write(&format!("{}/port", tty_path), "0x3F8\n")
+            .write(
+                "proc/tty/driver/serial",
+                "serinfo:1.0 driver revision:\n\
+                 0: uart:16550A port:000003F8 irq:4 tx:12 rx:34 fe:5 pe:1 brk:2 oe:3 RTS|CTS|DTR|DSR|CD\n\
+                 1: uart:unknown port:000002F8 irq:3\n",
+            );
+        let serial_list = get_serial_list_from(&fixture.root);
+        let uart_stats = serial_list[0].uart_stats.as_ref().unwrap();
+        assert_eq!(uart_stats.uart, "16550A");
+        assert_eq!((uart_stats.tx, uart_stats.rx), (12, 34));
+        assert_eq!(uart_stats.frame_errors, 5);
+        assert_eq!(uart_stats.overruns, 3);
+        assert!(uart_stats.cd && !uart_stats.ri);
+
+        // unreadable status is reported as soft error
+        fs::remove_file(fixture.base.join("proc/tty/driver/serial")).unwrap();
+        fs::create_dir(fixture.base.join("proc/tty/driver/serial")).unwrap();
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(serial_list[0].uart_stats, None);
+        assert!(matches!(
+            serial_list[0].errors[..],
+            [EnumerationError::Io(_, _)]
+        ));
+    }
+
+    #[test]
+    fn test_probe_pci_serial() {
+        let fixture = Fixture::new("pci");
+        let card = "sys/devices/pci0000:00/0000:00:1c.0/0000:03:00.0";
+        let lpss = "sys/devices/pci0000:00/0000:00:1e.0";
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "serial               /dev/ttyS       4 64-111 serial\n",
+            )
+            .write(&format!("{}/vendor", card), "0x13fe\n")
+            .write(&format!("{}/device", card), "0x1600\n")
+            .write(&format!("{}/subsystem_vendor", card), "0x13fe\n")
+            .write(&format!("{}/subsystem_device", card), "0x0002\n")
+            .write(&format!("{}/class", card), "0x070002\n")
+            .link(&format!("{}/subsystem", card), "sys/bus/pci")
+            .tty("ttyS4", card, "sys/bus/pci/drivers/serial")
+            .write(&format!("{}/vendor", lpss), "0x8086\n")
+            .write(&format!("{}/device", lpss), "0x9d27\n")
+            .link(&format!("{}/subsystem", lpss), "sys/bus/pci")
+            .link(
+                &format!("{}/dw-apb-uart.2/subsystem", lpss),
+                "sys/bus/platform",
+            )
+            .tty(
+                "ttyS5",
+                &format!("{}/dw-apb-uart.2", lpss),
+                "sys/bus/platform/drivers/dw-apb-uart",
+            );
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(serial_list.len(), 2);
+        assert_eq!(serial_list[0].vendor.as_deref(), Some("pci"));
+        assert_eq!(serial_list[0].product, None);
+        assert_eq!(
+            serial_list[0].pci_info,
+            Some(PciInfo {
+                vendor: "13fe".into(),
+                device: "1600".into(),
+                subsystem_vendor: Some("13fe".into()),
+                subsystem_device: Some("0002".into()),
+                class: Some("070002".into()),
+                slot: "0000:03:00.0".into(),
+            })
+        );
+        let pci_info = serial_list[1].pci_info.as_ref().unwrap();
+        assert_eq!(pci_info.slot, "0000:00:1e.0");
+        assert_eq!(pci_info.class, None);
+    }
+
+    #[test]
+    fn test_probe_bluetooth_serial() {
+        let fixture = Fixture::new("bluetooth");
+        fixture
+            .write(
+                "proc/tty/drivers",
+                "rfcomm               /dev/rfcomm   216 0-255 serial\n",
+            )
+            .write(
+                "sys/devices/virtual/tty/rfcomm0/address",
+                "00:12:34:56:78:9A\n",
+            )
+            .write("sys/devices/virtual/tty/rfcomm0/channel", "1\n")
+            .link("sys/class/tty/rfcomm0", "sys/devices/virtual/tty/rfcomm0")
+            .link(
+                "sys/class/bluetooth/hci0",
+                "sys/devices/virtual/bluetooth/hci0",
+            )
+            // the kernel lists rfcomm sockets without tty names or addresses
+            .write(
+                "proc/net/rfcomm",
+                "sk               RefCnt Rmem   Wmem   User   Inode  Parent\n\
+                 0000000000000000 2      0      0      1000   41726  0     \n",
+            );
+        let serial_list = get_serial_list_from(&fixture.root);
+        assert_eq!(serial_list.len(), 1);
+        assert_eq!(serial_list[0].kind, PortKind::Bluetooth);
+        assert_eq!(
+            serial_list[0].bluetooth_info,
+            Some(BluetoothInfo {
+                address: Some("00:12:34:56:78:9A".into()),
+                channel: Some(1),
+                adapter: Some("hci0".into()),
+            })
+        );
+
+        // a connected port is moved below the ACL connection of the adapter
+        let connection = "sys/devices/virtual/bluetooth/hci1/hci1:256";
+        fixture
+            .link(
+                "sys/class/bluetooth/hci1",
+                "sys/devices/virtual/bluetooth/hci1",
+            )
+            .link(
+                "sys/class/tty/rfcomm1",
+                &format!("{}/tty/rfcomm1", connection),
+            )
+            .link(&format!("{}/tty/rfcomm1/device", connection), connection);
+        let mut serial_list = get_serial_list_from(&fixture.root);
+        serial_list.sort_by(|a, b| a.name.cmp(&b.name));
+        let bluetooth_info = serial_list[1].bluetooth_info.as_ref().unwrap();
+        assert_eq!(bluetooth_info.adapter.as_deref(), Some("hci1"));
+    }
+
+    #[test]
+    fn test_enumeration_errors() {
+        let fixture = Fixture::new("errors");
+        assert!(matches!(
+            try_get_serial_list_from(&fixture.root),
+            Err(EnumerationError::SysfsMissing(_))
+        ));
+        assert!(get_serial_list_from(&fixture.root).is_empty());
+
+        let fixture = ft2232_fixture("soft-errors");
+        fs::write(
+            fixture.base.join(FT2232_DEVICE).join("serial"),
+            b"FT4Z\xffK3QA\n",
+        )
+        .unwrap();
+        let serial_list = try_get_serial_list_from(&fixture.root).unwrap();
+        assert_eq!(serial_list.len(), 1);
+        assert_eq!(
+            serial_list[0].usb_info.as_ref().unwrap().serial_number,
+            None
+        );
+        assert!(matches!(
+            serial_list[0].errors.as_slice(),
+            [EnumerationError::NonUtf8Attribute(_)]
+        ));
+
+        fixture.write("proc/tty/drivers", "/dev/ttyUSB usbserial\n");
+        assert!(matches!(
+            try_get_serial_list_from(&fixture.root),
+            Err(EnumerationError::MalformedDriverTable(_))
+        ));
+
+        // malformed lines of other drivers are skipped
+        fixture.write(
+            "proc/tty/drivers",
+            "/dev/vc/0 /dev/vc/0 4 0 system:vtmaster extra\n\
+             usbserial            /dev/ttyUSB   188 0-511 serial\n",
+        );
+        assert_eq!(list_tty_drivers_from(&fixture.root).len(), 1);
+        assert_eq!(try_get_serial_list_from(&fixture.root).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_serial_prefix_fallback() {
+        let denied = EnumerationError::PermissionDenied("/proc/tty/drivers".into());
+        let serial_prefix = serial_prefix_of(Err(denied)).unwrap();
+        assert_eq!(
+            serial_prefix.get("ttyUSB").map(String::as_str),
+            Some("usbserial")
+        );
+        assert!(serial_prefix.contains_key("ttyACM"));
+
+        let malformed = EnumerationError::MalformedDriverTable("/dev/ttyS serial".into());
+        assert!(serial_prefix_of(Err(malformed)).is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{SerialInfo, UsbInfo};
+use crate::{EnumerationError, PortKind, SerialInfo, UsbInfo};
 
 extern crate IOKit_sys;
 extern crate CoreFoundation_sys as cf;
@@ -141,13 +141,23 @@ fn get_serial_info(modem_service: io_iterator_t, name: &str) -> SerialInfo {
         let pid = get_int_property(usb_device, "idProduct", kCFNumberSInt16Type).unwrap_or_default();
         let vendor = get_string_property(usb_device, "USB Vendor Name");
         let product = get_string_property(usb_device, "USB Product Name");
+        let serial_number = get_string_property(usb_device, "USB Serial Number");
+        let bcd_device = get_int_property(usb_device, "bcdDevice", kCFNumberSInt16Type);
 
         return SerialInfo {
             name: name.to_string(),
             vendor,
             product,
             driver: None,
-            usb_info: Some(UsbInfo { vid: format!("{:x}", vid), pid: format!("{:x}", pid) })
+            kind: PortKind::Usb,
+            usb_info: Some(UsbInfo {
+                vid: format!("{:x}", vid),
+                pid: format!("{:x}", pid),
+                serial_number,
+                bcd_device: bcd_device.map(|bcd| format!("{:04x}", bcd)),
+                ..Default::default()
+            }),
+            ..Default::default()
         }
     }
     return SerialInfo {
@@ -155,7 +165,8 @@ fn get_serial_info(modem_service: io_iterator_t, name: &str) -> SerialInfo {
         vendor: None,
         product: None,
         driver: None,
-        usb_info: None
+        usb_info: None,
+        ..Default::default()
     };
 }
 
@@ -261,9 +272,12 @@ fn iokit_list() -> Result<Vec<SerialInfo>, String> {
     }
 }
 
+/// enumerate all avaliable serial port, errors are ignored
 pub fn get_serial_list() -> Vec<SerialInfo> {
-    match iokit_list() {
-        Ok(v) => return v,
-        Err(s) => panic!("{}", s)
-    }
+    return try_get_serial_list().unwrap_or_default();
+}
+
+/// enumerate all avaliable serial port
+pub fn try_get_serial_list() -> Result<Vec<SerialInfo>, EnumerationError> {
+    return iokit_list().map_err(EnumerationError::Platform);
 }
\ No newline at end of file
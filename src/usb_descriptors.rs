@@ -0,0 +1,465 @@
+//! Parser of raw usb descriptors, as found in the `descriptors` file of a usb device in sysfs:
+//! the device descriptor followed by the configuration descriptors and their interface,
+//! endpoint and class-specific descriptors.
+
+use nom::bytes::complete::take;
+use nom::number::complete::{le_u16, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+use std::fmt;
+
+const DEVICE: u8 = 0x01;
+const CONFIGURATION: u8 = 0x02;
+const INTERFACE: u8 = 0x04;
+const ENDPOINT: u8 = 0x05;
+const INTERFACE_ASSOCIATION: u8 = 0x0b;
+const CS_INTERFACE: u8 = 0x24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// descriptor tree of a usb device
+pub struct UsbDescriptors {
+    pub device: UsbDeviceDescriptor,
+    pub configurations: Vec<UsbConfigDescriptor>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// usb device descriptor, string descriptors are referenced by their index
+pub struct UsbDeviceDescriptor {
+    /// usb specification release, for example `0x0200`
+    pub bcd_usb: u16,
+    pub class: u8,
+    pub subclass: u8,
+    pub protocol: u8,
+    pub max_packet_size0: u8,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub bcd_device: u16,
+    pub manufacturer_index: u8,
+    pub product_index: u8,
+    pub serial_number_index: u8,
+    pub num_configurations: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// usb configuration descriptor with the descriptors which follow it
+pub struct UsbConfigDescriptor {
+    /// `bConfigurationValue`, which selects the configuration
+    pub value: u8,
+    pub configuration_index: u8,
+    /// `bmAttributes`, bit 6 self powered and bit 5 remote wakeup
+    pub attributes: u8,
+    /// `bMaxPower` in units of 2 mA, or 8 mA for SuperSpeed devices
+    pub max_power: u8,
+    /// interface association descriptors, which group the interfaces of a function
+    pub associations: Vec<UsbInterfaceAssociation>,
+    /// interfaces, one entry per alternate setting
+    pub interfaces: Vec<UsbInterfaceDescriptor>,
+    /// class-specific or unknown descriptors before the first interface
+    pub extra: Vec<UsbClassDescriptor>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// usb interface association descriptor
+pub struct UsbInterfaceAssociation {
+    pub first_interface: u8,
+    pub interface_count: u8,
+    pub function_class: u8,
+    pub function_subclass: u8,
+    pub function_protocol: u8,
+    pub function_index: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// usb interface descriptor with its endpoints
+pub struct UsbInterfaceDescriptor {
+    pub number: u8,
+    pub alternate_setting: u8,
+    pub class: u8,
+    pub subclass: u8,
+    pub protocol: u8,
+    pub interface_index: u8,
+    pub endpoints: Vec<UsbEndpointDescriptor>,
+    /// class-specific descriptors of the interface, such as CDC functional descriptors
+    pub extra: Vec<UsbClassDescriptor>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// usb endpoint descriptor
+pub struct UsbEndpointDescriptor {
+    /// `bEndpointAddress`, bit 7 is set for IN endpoints
+    pub address: u8,
+    /// `bmAttributes`, bits 0 and 1 are the transfer type
+    pub attributes: u8,
+    pub max_packet_size: u16,
+    pub interval: u8,
+    /// descriptors following the endpoint, such as the SuperSpeed endpoint companion
+    pub extra: Vec<UsbClassDescriptor>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// transfer type of an endpoint
+pub enum UsbTransferType {
+    Control,
+    Isochronous,
+    Bulk,
+    Interrupt,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// class-specific or unknown descriptor
+pub struct UsbClassDescriptor {
+    /// `bDescriptorType`, for example `0x24` for `CS_INTERFACE`
+    pub descriptor_type: u8,
+    /// content after `bLength` and `bDescriptorType`
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// raw descriptors which can not be parsed
+pub struct DescriptorError {
+    /// byte offset of the malformed descriptor
+    pub offset: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "malformed usb descriptor at offset {}: {}",
+            self.offset, self.reason
+        )
+    }
+}
+
+impl std::error::Error for DescriptorError {}
+
+impl UsbDescriptors {
+    /// parse raw descriptors, starting with the device descriptor
+    pub fn parse(raw: &[u8]) -> Result<UsbDescriptors, DescriptorError> {
+        let mut s = raw;
+        let mut device = None;
+        let mut configurations: Vec<UsbConfigDescriptor> = Vec::new();
+        while !s.is_empty() {
+            let offset = raw.len() - s.len();
+            let error = |reason| DescriptorError { offset, reason };
+            let (rest, (descriptor_type, body)) =
+                descriptor_parser(s).map_err(|_| error("truncated descriptor"))?;
+            s = rest;
+
+            if device.is_none() {
+                if descriptor_type != DEVICE {
+                    return Err(error("expected device descriptor"));
+                }
+                let (_, descriptor) =
+                    device_parser(body).map_err(|_| error("short device descriptor"))?;
+                device = Some(descriptor);
+                continue;
+            }
+            if descriptor_type == CONFIGURATION {
+                let (_, descriptor) =
+                    config_parser(body).map_err(|_| error("short configuration descriptor"))?;
+                configurations.push(descriptor);
+                continue;
+            }
+
+            let config = configurations
+                .last_mut()
+                .ok_or_else(|| error("descriptor outside of a configuration"))?;
+            match descriptor_type {
+                INTERFACE_ASSOCIATION => {
+                    let (_, descriptor) = association_parser(body)
+                        .map_err(|_| error("short interface association descriptor"))?;
+                    config.associations.push(descriptor);
+                }
+                INTERFACE => {
+                    let (_, descriptor) =
+                        interface_parser(body).map_err(|_| error("short interface descriptor"))?;
+                    config.interfaces.push(descriptor);
+                }
+                ENDPOINT => {
+                    let (_, descriptor) =
+                        endpoint_parser(body).map_err(|_| error("short endpoint descriptor"))?;
+                    config
+                        .interfaces
+                        .last_mut()
+                        .ok_or_else(|| error("endpoint outside of an interface"))?
+                        .endpoints
+                        .push(descriptor);
+                }
+                _ => {
+                    let descriptor = UsbClassDescriptor {
+                        descriptor_type,
+                        data: body.to_vec(),
+                    };
+                    // class-specific descriptors belong to the preceding endpoint or interface
+                    match config.interfaces.last_mut() {
+                        Some(interface) => match interface.endpoints.last_mut() {
+                            Some(endpoint) => endpoint.extra.push(descriptor),
+                            None => interface.extra.push(descriptor),
+                        },
+                        None => config.extra.push(descriptor),
+                    }
+                }
+            }
+        }
+        let device = device.ok_or(DescriptorError {
+            offset: 0,
+            reason: "missing device descriptor",
+        })?;
+        return Ok(UsbDescriptors {
+            device,
+            configurations,
+        });
+    }
+
+    /// configuration with `bConfigurationValue` equal to `value`
+    pub fn configuration(&self, value: u8) -> Option<&UsbConfigDescriptor> {
+        return self
+            .configurations
+            .iter()
+            .find(|config| config.value == value);
+    }
+}
+
+impl UsbConfigDescriptor {
+    /// alternate setting `alternate_setting` of interface `number`
+    pub fn interface(&self, number: u8, alternate_setting: u8) -> Option<&UsbInterfaceDescriptor> {
+        return self.interfaces.iter().find(|interface| {
+            interface.number == number && interface.alternate_setting == alternate_setting
+        });
+    }
+
+    /// interface association which contains interface `number`
+    pub fn association(&self, number: u8) -> Option<&UsbInterfaceAssociation> {
+        return self.associations.iter().find(|association| {
+            let first = association.first_interface;
+            number >= first && number - first < association.interface_count
+        });
+    }
+}
+
+impl UsbInterfaceDescriptor {
+    /// content after `bDescriptorSubtype` of the first class-specific interface descriptor
+    /// of `subtype`, such as a CDC functional descriptor
+    pub fn functional_descriptor(&self, subtype: u8) -> Option<&[u8]> {
+        return self
+            .extra
+            .iter()
+            .filter(|descriptor| descriptor.descriptor_type == CS_INTERFACE)
+            .find(|descriptor| descriptor.data.first() == Some(&subtype))
+            .map(|descriptor| &descriptor.data[1..]);
+    }
+}
+
+impl UsbEndpointDescriptor {
+    /// endpoint number without direction bit
+    pub fn number(&self) -> u8 {
+        return self.address & 0x0f;
+    }
+
+    /// the endpoint transfers from device to host
+    pub fn is_in(&self) -> bool {
+        return self.address & 0x80 != 0;
+    }
+
+    pub fn transfer_type(&self) -> UsbTransferType {
+        return match self.attributes & 0x03 {
+            0 => UsbTransferType::Control,
+            1 => UsbTransferType::Isochronous,
+            2 => UsbTransferType::Bulk,
+            _ => UsbTransferType::Interrupt,
+        };
+    }
+}
+
+/// split a descriptor into its type and the content after `bLength` and `bDescriptorType`
+fn descriptor_parser(s: &[u8]) -> IResult<&[u8], (u8, &[u8])> {
+    let (s, length) = le_u8(s)?;
+    if length < 2 {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            s,
+            nom::error::ErrorKind::LengthValue,
+        )));
+    }
+    let (s, descriptor_type) = le_u8(s)?;
+    let (s, body) = take(length - 2)(s)?;
+    return Ok((s, (descriptor_type, body)));
+}
+
+fn device_parser(s: &[u8]) -> IResult<&[u8], UsbDeviceDescriptor> {
+    let (s, fields) = tuple((
+        le_u16, le_u8, le_u8, le_u8, le_u8, le_u16, le_u16, le_u16, le_u8, le_u8, le_u8, le_u8,
+    ))(s)?;
+    let (
+        bcd_usb,
+        class,
+        subclass,
+        protocol,
+        max_packet_size0,
+        vendor_id,
+        product_id,
+        bcd_device,
+        manufacturer_index,
+        product_index,
+        serial_number_index,
+        num_configurations,
+    ) = fields;
+    let descriptor = UsbDeviceDescriptor {
+        bcd_usb,
+        class,
+        subclass,
+        protocol,
+        max_packet_size0,
+        vendor_id,
+        product_id,
+        bcd_device,
+        manufacturer_index,
+        product_index,
+        serial_number_index,
+        num_configurations,
+    };
+    return Ok((s, descriptor));
+}
+
+fn config_parser(s: &[u8]) -> IResult<&[u8], UsbConfigDescriptor> {
+    // wTotalLength and bNumInterfaces are implied by the following descriptors
+    let (s, (_, _, value, configuration_index, attributes, max_power)) =
+        tuple((le_u16, le_u8, le_u8, le_u8, le_u8, le_u8))(s)?;
+    let descriptor = UsbConfigDescriptor {
+        value,
+        configuration_index,
+        attributes,
+        max_power,
+        ..Default::default()
+    };
+    return Ok((s, descriptor));
+}
+
+fn association_parser(s: &[u8]) -> IResult<&[u8], UsbInterfaceAssociation> {
+    let (s, fields) = tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8))(s)?;
+    let (first_interface, interface_count, function_class, function_subclass) =
+        (fields.0, fields.1, fields.2, fields.3);
+    let descriptor = UsbInterfaceAssociation {
+        first_interface,
+        interface_count,
+        function_class,
+        function_subclass,
+        function_protocol: fields.4,
+        function_index: fields.5,
+    };
+    return Ok((s, descriptor));
+}
+
+fn interface_parser(s: &[u8]) -> IResult<&[u8], UsbInterfaceDescriptor> {
+    // bNumEndpoints is implied by the following endpoint descriptors
+    let (s, (number, alternate_setting, _, class, subclass, protocol, interface_index)) =
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8))(s)?;
+    let descriptor = UsbInterfaceDescriptor {
+        number,
+        alternate_setting,
+        class,
+        subclass,
+        protocol,
+        interface_index,
+        ..Default::default()
+    };
+    return Ok((s, descriptor));
+}
+
+fn endpoint_parser(s: &[u8]) -> IResult<&[u8], UsbEndpointDescriptor> {
+    let (s, (address, attributes, max_packet_size, interval)) =
+        tuple((le_u8, le_u8, le_u16, le_u8))(s)?;
+    let descriptor = UsbEndpointDescriptor {
+        address,
+        attributes,
+        max_packet_size,
+        interval,
+        ..Default::default()
+    };
+    return Ok((s, descriptor));
+}
+
+/// descriptors of an Arduino Uno R3, a CDC-ACM device with IAD
+#[cfg(test)]
+pub(crate) const ARDUINO_UNO: &[u8] = &[
+    // device
+    0x12, 0x01, 0x10, 0x01, 0x02, 0x00, 0x00, 0x08, 0x41, 0x23, 0x43, 0x00, 0x01, 0x00, 0x01, 0x02,
+    0xdc, 0x01, //
+    // configuration 1
+    0x09, 0x02, 0x4b, 0x00, 0x02, 0x01, 0x00, 0xc0, 0x32, //
+    // interface association of interfaces 0 and 1
+    0x08, 0x0b, 0x00, 0x02, 0x02, 0x02, 0x01, 0x00, //
+    // communication interface 0
+    0x09, 0x04, 0x00, 0x00, 0x01, 0x02, 0x02, 0x01, 0x00, //
+    // CDC header, call management, ACM and union functional descriptors
+    0x05, 0x24, 0x00, 0x10, 0x01, //
+    0x05, 0x24, 0x01, 0x01, 0x01, //
+    0x04, 0x24, 0x02, 0x06, //
+    0x05, 0x24, 0x06, 0x00, 0x01, //
+    // notification endpoint
+    0x07, 0x05, 0x82, 0x03, 0x08, 0x00, 0xff, //
+    // data interface 1 with bulk endpoints
+    0x09, 0x04, 0x01, 0x00, 0x02, 0x0a, 0x00, 0x00, 0x00, //
+    0x07, 0x05, 0x04, 0x02, 0x40, 0x00, 0x01, //
+    0x07, 0x05, 0x83, 0x02, 0x40, 0x00, 0x01,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let descriptors = UsbDescriptors::parse(ARDUINO_UNO).unwrap();
+        assert_eq!(descriptors.device.vendor_id, 0x2341);
+        assert_eq!(descriptors.device.product_id, 0x0043);
+        assert_eq!(descriptors.device.serial_number_index, 0xdc);
+
+        let config = descriptors.configuration(1).unwrap();
+        assert_eq!(config.max_power, 0x32);
+        assert_eq!(config.interfaces.len(), 2);
+        assert_eq!(config.association(1).unwrap().function_class, 0x02);
+
+        let control = config.interface(0, 0).unwrap();
+        assert_eq!(control.class, 0x02);
+        assert_eq!(control.extra.len(), 4);
+        assert_eq!(control.extra[2].data, [0x02, 0x06]);
+        assert_eq!(control.functional_descriptor(0x06), Some(&[0x00, 0x01][..]));
+        assert_eq!(control.functional_descriptor(0x07), None);
+        assert_eq!(
+            control.endpoints[0].transfer_type(),
+            UsbTransferType::Interrupt
+        );
+
+        let data = config.interface(1, 0).unwrap();
+        let endpoints: Vec<_> = data
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                (
+                    endpoint.number(),
+                    endpoint.is_in(),
+                    endpoint.transfer_type(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            endpoints,
+            [
+                (4, false, UsbTransferType::Bulk),
+                (3, true, UsbTransferType::Bulk)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let error = UsbDescriptors::parse(&ARDUINO_UNO[..ARDUINO_UNO.len() - 1]).unwrap_err();
+        assert_eq!(error.offset, ARDUINO_UNO.len() - 7);
+        let error = UsbDescriptors::parse(&ARDUINO_UNO[18..]).unwrap_err();
+        assert_eq!(error.reason, "expected device descriptor");
+        assert!(UsbDescriptors::parse(&[0x00, 0x01]).is_err());
+        assert!(UsbDescriptors::parse(&[]).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Compact subset of the [usb.ids](http://www.linux-usb.org/usb.ids) database,
+//! covering vendors and products of common usb serial adapters and boards.
+
+/// vendor id, vendor name and products of the vendor, sorted by product id
+type Vendor = (u16, &'static str, &'static [(u16, &'static str)]);
+
+/// vendors sorted by vendor id
+static USB_IDS: &[Vendor] = &[
+    (
+        0x0403,
+        "Future Technology Devices International, Ltd",
+        &[
+            (0x6001, "FT232 Serial (UART) IC"),
+            (0x6010, "FT2232C/D/H Dual UART/FIFO IC"),
+            (0x6011, "FT4232H Quad HS USB-UART/FIFO IC"),
+            (0x6014, "FT232H Single HS USB-UART/FIFO IC"),
+            (0x6015, "Bridge(I2C/SPI/UART/FIFO)"),
+        ],
+    ),
+    (
+        0x0483,
+        "STMicroelectronics",
+        &[(0x374b, "ST-LINK/V2.1"), (0x5740, "Virtual COM Port")],
+    ),
+    (
+        0x04d8,
+        "Microchip Technology, Inc.",
+        &[
+            (0x000a, "CDC RS-232 Emulation Demo"),
+            (0x00dd, "MCP2221 USB-I2C/UART Combo"),
+        ],
+    ),
+    (
+        0x0525,
+        "Netchip Technology, Inc.",
+        &[
+            (0xa4a6, "Linux-USB Serial Gadget"),
+            (0xa4a7, "Linux-USB Serial Gadget (CDC ACM mode)"),
+        ],
+    ),
+    (
+        0x0557,
+        "ATEN International Co., Ltd",
+        &[(0x2008, "UC-232A Serial Port [pl2303]")],
+    ),
+    (
+        0x067b,
+        "Prolific Technology, Inc.",
+        &[
+            (0x2303, "PL2303 Serial Port / Mobile Action MA-8910P"),
+            (0x23a3, "PL2303GC Serial Port"),
+            (0x23b3, "PL2303GB Serial Port"),
+            (0x23c3, "PL2303GT Serial Port"),
+            (0x23d3, "PL2303GL Serial Port"),
+            (0x23e3, "PL2303GE Serial Port"),
+            (0x23f3, "PL2303GS Serial Port"),
+        ],
+    ),
+    (
+        0x10c4,
+        "Silicon Labs",
+        &[
+            (0xea60, "CP210x UART Bridge"),
+            (0xea70, "CP2105 Dual UART Bridge"),
+            (0xea71, "CP2108 Quad UART Bridge"),
+        ],
+    ),
+    (
+        0x110a,
+        "Moxa Technologies Co., Ltd.",
+        &[
+            (0x1110, "UPort 1110"),
+            (0x1150, "UPort 1150 1-Port RS-232/422/485"),
+            (0x1250, "UPort 1250 2-Port RS-232/422/485"),
+            (0x1410, "UPort 1410 4-Port RS-232"),
+            (0x1450, "UPort 1450 4-Port RS-232/422/485"),
+        ],
+    ),
+    (
+        0x1366,
+        "SEGGER",
+        &[
+            (0x0101, "J-Link PLUS"),
+            (0x0105, "J-Link"),
+            (0x1015, "J-Link"),
+        ],
+    ),
+    (
+        0x1546,
+        "U-Blox AG",
+        &[(0x01a7, "[u-blox 7]"), (0x01a8, "[u-blox 8]")],
+    ),
+    (
+        0x16c0,
+        "Van Ooijen Technische Informatica",
+        &[(0x0483, "Teensyduino Serial")],
+    ),
+    (
+        0x1915,
+        "Nordic Semiconductor ASA",
+        &[
+            (0x521f, "Open DFU Bootloader"),
+            (0xc00a, "nRF52 Connectivity"),
+        ],
+    ),
+    (
+        0x1a86,
+        "QinHeng Electronics",
+        &[
+            (0x5523, "CH341 in serial mode, usb to serial port converter"),
+            (0x55d3, "CH343 serial converter"),
+            (0x55d4, "CH9102 serial converter"),
+            (0x7523, "CH340 serial converter"),
+        ],
+    ),
+    (
+        0x1d50,
+        "OpenMoko, Inc.",
+        &[(0x6018, "Black Magic Debug Probe (Application)")],
+    ),
+    (
+        0x2341,
+        "Arduino SA",
+        &[
+            (0x0001, "Uno (CDC ACM)"),
+            (0x0042, "Mega 2560 R3 (CDC ACM)"),
+            (0x0043, "Uno R3 (CDC ACM)"),
+            (0x8036, "Leonardo (CDC ACM, HID)"),
+        ],
+    ),
+    (
+        0x239a,
+        "Adafruit",
+        &[(0x800b, "Feather M0"), (0x8014, "Metro M0 Express")],
+    ),
+    (
+        0x2e8a,
+        "Raspberry Pi",
+        &[
+            (0x0003, "RP2040 Boot"),
+            (0x0004, "Picoprobe"),
+            (0x0005, "Board in MicroPython mode"),
+            (0x000a, "Pico"),
+            (0x000c, "Debug Probe (CMSIS-DAP)"),
+        ],
+    ),
+    (
+        0x303a,
+        "Espressif",
+        &[(0x1001, "USB JTAG/serial debug unit")],
+    ),
+];
+
+fn parse_id(id: &str) -> Option<u16> {
+    return u16::from_str_radix(id, 16).ok();
+}
+
+fn find_vendor(vid: u16) -> Option<&'static Vendor> {
+    return USB_IDS
+        .binary_search_by_key(&vid, |(id, _, _)| *id)
+        .ok()
+        .map(|i| &USB_IDS[i]);
+}
+
+/// vendor name of the hex vendor id `vid`
+pub fn vendor_name(vid: &str) -> Option<&'static str> {
+    return find_vendor(parse_id(vid)?).map(|(_, name, _)| *name);
+}
+
+/// product name of the hex vendor id `vid` and product id `pid`
+pub fn product_name(vid: &str, pid: &str) -> Option<&'static str> {
+    let pid = parse_id(pid)?;
+    let (_, _, products) = find_vendor(parse_id(vid)?)?;
+    return products
+        .binary_search_by_key(&pid, |(id, _)| *id)
+        .ok()
+        .map(|i| products[i].1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sorted() {
+        assert!(USB_IDS.windows(2).all(|w| w[0].0 < w[1].0));
+        for (_, _, products) in USB_IDS {
+            assert!(products.windows(2).all(|w| w[0].0 < w[1].0));
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(vendor_name("1A86"), Some("QinHeng Electronics"));
+        assert_eq!(product_name("1a86", "7523"), Some("CH340 serial converter"));
+        assert_eq!(
+            product_name("1a86", "55D4"),
+            Some("CH9102 serial converter")
+        );
+        assert_eq!(product_name("067b", "23c3"), Some("PL2303GT Serial Port"));
+        assert_eq!(product_name("1a86", "0000"), None);
+        assert_eq!(vendor_name("zzzz"), None);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{SerialInfo, UsbInfo};
+use crate::{EnumerationError, PortKind, SerialInfo, UsbInfo};
 use core::ffi::c_void;
 use std::mem::size_of;
 use windows::core::GUID;
@@ -109,8 +109,7 @@ unsafe fn get_port_name_from_dev_info(
     RegCloseKey(hkey);
     if result == 0 {
         buffer.set_len(name_size as usize - 1);
-        let port_name = String::from_utf8(buffer).unwrap();
-        return Some(port_name);
+        return String::from_utf8(buffer).ok();
     }
     return None;
 }
@@ -128,9 +127,15 @@ unsafe fn get_usb_info(dev_set: *const c_void, dev_inf: &SP_DEVINFO_DATA) -> Opt
     .as_bool()
     {
         buffer.set_len(id_size as usize - 1);
-        let device_id = String::from_utf8(buffer).unwrap();
+        let device_id = String::from_utf8(buffer).ok()?;
         return device_id_parser::parse_device_id(device_id.as_str())
-            .and_then(|(vid, pid)| Some(UsbInfo { vid, pid }));
+            .and_then(|(vid, pid)| {
+                Some(UsbInfo {
+                    vid,
+                    pid,
+                    ..Default::default()
+                })
+            });
     }
     return None;
 }
@@ -175,20 +180,33 @@ unsafe fn get_serial_info(
     dev_set: *const c_void,
     dev_inf: &SP_DEVINFO_DATA,
 ) -> SerialInfo {
+    let usb_info = get_usb_info(dev_set, dev_inf);
     return SerialInfo {
         name,
         vendor: get_serial_property(dev_set, dev_inf, SPDRP_MFG),
         product: get_serial_property(dev_set, dev_inf, SPDRP_DEVICEDESC),
         driver: None,
-        usb_info: get_usb_info(dev_set, dev_inf),
+        kind: match usb_info {
+            Some(_) => PortKind::Usb,
+            None => PortKind::Unknown,
+        },
+        usb_info,
+        ..Default::default()
     };
 }
 
-/// enumerate all avaliable serial port
+/// enumerate all avaliable serial port, errors are ignored
 pub fn get_serial_list() -> Vec<SerialInfo> {
+    return try_get_serial_list().unwrap_or_default();
+}
+
+/// enumerate all avaliable serial port
+pub fn try_get_serial_list() -> Result<Vec<SerialInfo>, EnumerationError> {
     let mut serial_list = Vec::new();
-    let guids = get_device_class_guids_form_serial();
-    for guid in guids.unwrap() {
+    let guids = get_device_class_guids_form_serial().ok_or_else(|| {
+        EnumerationError::Platform("failed to get class guids of \"Ports\"".into())
+    })?;
+    for guid in guids {
         unsafe {
             let dev_set = SetupDiGetClassDevsA(&guid, None, None, DIGCF_PRESENT);
             if dev_set > std::ptr::null_mut() {
@@ -222,5 +240,5 @@ pub fn get_serial_list() -> Vec<SerialInfo> {
             }
         }
     }
-    return serial_list;
+    return Ok(serial_list);
 }